@@ -160,7 +160,98 @@ fn test_logic(n: i32) -> i32 {
 }
 
 // ----------------------------------------------------------------------------
-// 8. Test: Recursion (Fibonacci)
+// 8. Test: Bitwise & Shift Operators (Signed vs. Unsigned)
+// ----------------------------------------------------------------------------
+fn test_bitwise() {
+    a: i32 = 10 | 5;   // 0b1010 | 0b0101 -> 15
+    b: i32 = 12 & 10;  // 0b1100 & 0b1010 -> 8
+    c: i32 = 12 ^ 10;  // 0b1100 ^ 0b1010 -> 6
+
+    neg: i32 = -8;
+    arith: i32 = neg >> 1; // arithmetic shift: sign-fill -> -4
+
+    pos: u32 = 4000000000;
+    logical: u32 = pos >> 1; // logical shift: zero-fill, stays positive
+
+    wide: u64 = 1;
+    shifted: u64 = wide << 40; // well past 32 bits, fine on a u64
+
+    narrow: i32 = 1;
+    masked: i32 = narrow << 40; // shift amount masked to 5 bits -> same as << 8
+
+    combo: i32 = (GLOBAL_X & 255) | (1 << 4);
+    flipped: i32 = ~GLOBAL_X;
+}
+
+// ----------------------------------------------------------------------------
+// 9. Test: Explicit Casts (`as`)
+// ----------------------------------------------------------------------------
+fn test_casts() {
+    big: i64 = 4294967296;       // 2^32, doesn't fit in i32
+    trunc: i32 = big as i32;     // truncation drops the high bits -> 0
+
+    neg: i32 = -1;
+    widened: i64 = neg as i64;   // sign-extension -> -1, not 4294967295
+    zero_ext: u64 = neg as u32 as u64; // reinterpret as u32 first, then zero-extend
+
+    pi: f64 = GLOBAL_PI;
+    rounded: i32 = pi as i32;    // round-to-nearest -> 3, not truncated
+
+    huge: f64 = 1.0e300;
+    saturated: i32 = huge as i32; // out of range -> saturates to i32::MAX
+
+    val: i32 = 50;
+    ptr: i32* = &val;
+    addr: i64 = ptr as i64;       // pointer -> integer reinterpretation
+    ptr_back: i32* = addr as i32*; // integer -> pointer, round-trips to `val`
+    same: i32 = *ptr_back;
+}
+
+// ----------------------------------------------------------------------------
+// 10. Test: Math Intrinsics
+// ----------------------------------------------------------------------------
+fn test_intrinsics() {
+    // Integer literal promotes to the f64 default, same as add_f64(10, 2.5)
+    root: f64 = sqrt(4);       // 2.0
+
+    half_turn: f64 = GLOBAL_PI;
+    wave: f64 = sin(half_turn);
+
+    angle32: f32 = 0.0;
+    wave32: f32 = cos(angle32); // stays f32, doesn't promote to f64
+
+    rounded_down: f64 = floor(3.7); // 3.0
+
+    signed: f64 = copysign(5.0, -1.0); // -5.0
+
+    doubled: f64 = scalbn(1.5, 1); // 3.0
+}
+
+// ----------------------------------------------------------------------------
+// 11. Test: Enums
+// ----------------------------------------------------------------------------
+enum Direction { North, East, South, West }
+enum Toggle { Off, On }
+
+fn test_enums() {
+    // A. Variant access & the underlying-integer discriminant
+    d: Direction = Direction::South;
+    d_as_int: i32 = d as i32; // 2
+
+    // B. Two-variant enums get a boolean representation: usable
+    // directly as an `if` condition, no `!= 0` needed
+    t: Toggle = Toggle::On;
+    flag: i32 = 0;
+    if (t) {
+        flag = 1;
+    }
+
+    // C. Equality between values of the same enum
+    is_west: bool = d == Direction::West; // false
+}
+
+// ----------------------------------------------------------------------------
+// 12. Test: Recursion (Fibonacci)
 // ----------------------------------------------------------------------------
 fn fib(n: i64) -> i64 {
     if (n <= 1) { 
@@ -178,7 +269,11 @@ fn main() {
     test_pointers();
     test_arrays();
     test_complex_types();
-    
+    test_bitwise();
+    test_casts();
+    test_intrinsics();
+    test_enums();
+
     // Verify Logic
     logic_res: i32 = test_logic(6); // Should be 20
     
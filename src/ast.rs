@@ -0,0 +1,122 @@
+//! Abstract syntax tree produced by the parser and consumed by the
+//! type-checker and evaluator.
+
+use crate::types::Type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+impl BinOp {
+    /// Bitwise/shift operators only make sense on integers; rejecting
+    /// f32/f64 operands here is what `test_promotions`-style float code
+    /// relies on never silently reinterpreting bits.
+    pub fn is_bitwise(&self) -> bool {
+        matches!(self, BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor | BinOp::Shl | BinOp::Shr)
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(self, BinOp::Lt | BinOp::Gt | BinOp::Le | BinOp::Ge | BinOp::Eq | BinOp::Ne)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Ref,
+    Deref,
+    BitNot,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    IntLit(i64),
+    FloatLit(f64),
+    BoolLit(bool),
+    Ident(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    ArrayLit(Vec<Expr>),
+    Cast(Box<Expr>, Type),
+    /// `EnumName::Variant`.
+    EnumVariant(String, String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    Let { name: String, ty: Type, init: Expr },
+    Assign { target: Expr, value: Expr },
+    Expr(Expr),
+    Return(Option<Expr>),
+    If { cond: Expr, then_branch: Vec<Stmt>, else_branch: Option<Vec<Stmt>> },
+    While { cond: Expr, body: Vec<Stmt> },
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    pub ty: Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub ret: Type,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Const {
+    pub name: String,
+    pub ty: Type,
+    pub value: Expr,
+}
+
+/// A user-defined enum: an ordered list of variants, each assigned the
+/// integer discriminant matching its position, stored at `underlying`
+/// (defaults to `i32` when not written explicitly - see `parse_enum`).
+#[derive(Debug, Clone)]
+pub struct EnumDecl {
+    pub name: String,
+    pub underlying: Type,
+    pub variants: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Item {
+    Function(Function),
+    Const(Const),
+    Enum(EnumDecl),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    pub items: Vec<Item>,
+}
+
+/// One line of REPL input: either a top-level declaration or a single
+/// statement evaluated against the REPL's persistent session.
+#[derive(Debug, Clone)]
+pub enum ReplEntry {
+    Item(Item),
+    Stmt(Stmt),
+}
@@ -0,0 +1,19 @@
+//! Diagnostics shared across the front end. The lexer and parser still
+//! panic on malformed input (there is no recovery story yet), but the
+//! type-checker reports through `TypeError` so callers can decide how to
+//! surface a failed compile.
+
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct TypeError(pub String);
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+pub type TypeResult<T> = Result<T, TypeError>;
@@ -0,0 +1,845 @@
+//! Tree-walking evaluator: the native backend that executes a
+//! type-checked `Program` directly, without lowering to any lower-level
+//! IR. Pointers are modeled as shared, mutable cells so `&val` / `*ptr`
+//! and `*ptr = ...` work without unsafe code.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::ast::*;
+use crate::intrinsics::{self, Intrinsic};
+use crate::types::Type;
+
+pub type Cell = Rc<RefCell<Value>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Ptr(Cell),
+    Array(Rc<Vec<Cell>>),
+    FnPtr(String),
+    /// A value of a user-defined enum: its type name and the variant's
+    /// integer discriminant (position in declaration order).
+    Enum(String, i64),
+    Void,
+}
+
+impl Value {
+    pub fn type_of(&self) -> Type {
+        match self {
+            Value::I32(_) => Type::I32,
+            Value::I64(_) => Type::I64,
+            Value::U32(_) => Type::U32,
+            Value::U64(_) => Type::U64,
+            Value::F32(_) => Type::F32,
+            Value::F64(_) => Type::F64,
+            Value::Bool(_) => Type::Bool,
+            Value::Ptr(cell) => Type::Pointer(Box::new(cell.borrow().type_of())),
+            Value::Array(cells) => {
+                let elem = cells.first().map(|c| c.borrow().type_of()).unwrap_or(Type::Void);
+                Type::Array(Box::new(elem), Some(cells.len()))
+            }
+            Value::FnPtr(_) => Type::Void,
+            Value::Enum(name, _) => Type::Enum(name.clone()),
+            Value::Void => Type::Void,
+        }
+    }
+
+    fn as_i64(&self) -> i64 {
+        match self {
+            Value::I32(n) => *n as i64,
+            Value::I64(n) => *n,
+            Value::U32(n) => *n as i64,
+            Value::U64(n) => *n as i64,
+            // An enum's underlying representation is its discriminant,
+            // same idea as `cast_to`/`explicit_cast` reusing this for
+            // every other numeric type.
+            Value::Enum(_, d) => *d,
+            _ => panic!("expected integer value, found {:?}", self),
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            Value::F32(n) => *n as f64,
+            Value::F64(n) => *n,
+            Value::I32(n) => *n as f64,
+            Value::I64(n) => *n as f64,
+            Value::U32(n) => *n as f64,
+            Value::U64(n) => *n as f64,
+            _ => panic!("expected numeric value, found {:?}", self),
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            // Boolean-representation optimization: a two-variant enum is
+            // usable directly as an `if`/`while` condition (the
+            // type-checker's `is_condition_type` is what actually limits
+            // this to two-variant enums; by the time a value gets here
+            // it's already been accepted), same as a real `bool`.
+            Value::Enum(_, d) => *d != 0,
+            _ => panic!("expected bool value, found {:?}", self),
+        }
+    }
+}
+
+/// Promotes two values to their common type, mirroring
+/// `types::Type::promote` at the value level.
+fn promote_pair(a: Value, b: Value) -> (Value, Value, Type) {
+    let common = Type::promote(&a.type_of(), &b.type_of())
+        .unwrap_or_else(|| panic!("cannot promote {:?} and {:?}", a, b));
+    (cast_to(a, &common), cast_to(b, &common), common)
+}
+
+fn cast_to(v: Value, ty: &Type) -> Value {
+    if v.type_of() == *ty {
+        return v;
+    }
+    if let (Value::FnPtr(_), Type::Function(_, _)) = (&v, ty) {
+        // `Value::type_of()` can't recover a function pointer's
+        // parameter/return types (it only has the resolved name), so
+        // the fast path above never matches even though the value is
+        // already exactly representable at the declared type.
+        return v;
+    }
+    if let (Value::Array(cells), Type::Array(elem_ty, _)) = (&v, ty) {
+        // An array literal's elements are evaluated at native width (see
+        // the `IntLit` comment in `eval_expr`) and only narrowed to the
+        // declared element type here, element-wise - same idea as a
+        // scalar `let` binding. This also covers an inferred-size
+        // declaration (`i32[]`): the resulting length is just whatever
+        // falls out of casting each element, no separate bookkeeping.
+        let cells = cells.iter().map(|c| Rc::new(RefCell::new(cast_to(c.borrow().clone(), elem_ty)))).collect();
+        return Value::Array(Rc::new(cells));
+    }
+    match ty {
+        Type::I32 => Value::I32(v.as_i64() as i32),
+        Type::I64 => Value::I64(v.as_i64()),
+        Type::U32 => Value::U32(v.as_i64() as u32),
+        Type::U64 => Value::U64(v.as_i64() as u64),
+        Type::F32 => Value::F32(v.as_f64() as f32),
+        Type::F64 => Value::F64(v.as_f64()),
+        other => panic!("cannot cast value to {}", other),
+    }
+}
+
+enum Signal {
+    Normal,
+    Return(Value),
+}
+
+pub struct Interpreter {
+    functions: HashMap<String, Rc<Function>>,
+    globals: HashMap<String, Value>,
+    enums: HashMap<String, EnumDecl>,
+    scopes: Vec<HashMap<String, Cell>>,
+    /// Remembers every cell that has been cast to an integer address (via
+    /// `ptr as i64`), keyed by that cell's Rc allocation address, so a
+    /// later `addr as T*` can reinterpret its way back to the same cell
+    /// instead of needing a real flat memory model.
+    addr_table: HashMap<usize, Cell>,
+}
+
+impl Interpreter {
+    pub fn new(program: &Program) -> Self {
+        let mut interp = Interpreter {
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            enums: HashMap::new(),
+            scopes: Vec::new(),
+            addr_table: HashMap::new(),
+        };
+        for item in &program.items {
+            if let Item::Function(f) = item {
+                interp.functions.insert(f.name.clone(), Rc::new(f.clone()));
+            }
+            if let Item::Enum(e) = item {
+                interp.enums.insert(e.name.clone(), e.clone());
+            }
+        }
+        for item in &program.items {
+            if let Item::Const(c) = item {
+                let value = interp.eval_expr(&c.value);
+                let value = cast_to(value, &c.ty);
+                interp.globals.insert(c.name.clone(), value);
+            }
+        }
+        interp
+    }
+
+    /// Constructs an interpreter for REPL use: there's no whole `Program`
+    /// to seed `functions`/`globals` from up front, so entries are
+    /// declared one at a time via `declare_item`. `scopes` is seeded with
+    /// one scope that's never popped, so a `let`-bound local survives
+    /// from one entry to the next the same way `new_repl`'s session scope
+    /// does for the type-checker.
+    pub fn new_repl() -> Self {
+        Interpreter {
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            enums: HashMap::new(),
+            scopes: vec![HashMap::new()],
+            addr_table: HashMap::new(),
+        }
+    }
+
+    /// Declares a `const`, `fn`, or `enum` entered at the REPL, mirroring
+    /// the branches `Interpreter::new` runs over a whole program's items.
+    pub fn declare_item(&mut self, item: &Item) {
+        match item {
+            Item::Function(f) => {
+                self.functions.insert(f.name.clone(), Rc::new(f.clone()));
+            }
+            Item::Const(c) => {
+                let value = self.eval_expr(&c.value);
+                let value = cast_to(value, &c.ty);
+                self.globals.insert(c.name.clone(), value);
+            }
+            Item::Enum(e) => {
+                self.enums.insert(e.name.clone(), e.clone());
+            }
+        }
+    }
+
+    /// Evaluates one REPL statement, returning the value of a bare
+    /// expression statement (e.g. `1 + 2;`) for the REPL to print; other
+    /// statement kinds (`let`, assignment, control flow) just run for
+    /// their effect on the persistent session scope.
+    pub fn eval_repl_stmt(&mut self, stmt: &Stmt) -> Option<Value> {
+        if let Stmt::Expr(e) = stmt {
+            return Some(self.eval_expr(e));
+        }
+        self.eval_stmt(stmt);
+        None
+    }
+
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Value {
+        let f = self.functions.get(name).expect("undefined function").clone();
+        self.call_function(&f, args)
+    }
+
+    fn call_function(&mut self, f: &Function, args: Vec<Value>) -> Value {
+        let mut scope = HashMap::new();
+        for (param, arg) in f.params.iter().zip(args) {
+            scope.insert(param.name.clone(), Rc::new(RefCell::new(cast_to(arg, &param.ty))));
+        }
+        self.scopes.push(scope);
+        let result = match self.eval_block(&f.body) {
+            Signal::Return(v) => v,
+            Signal::Normal => Value::Void,
+        };
+        self.scopes.pop();
+        result
+    }
+
+    fn eval_block(&mut self, stmts: &[Stmt]) -> Signal {
+        self.scopes.push(HashMap::new());
+        let mut signal = Signal::Normal;
+        for stmt in stmts {
+            signal = self.eval_stmt(stmt);
+            if matches!(signal, Signal::Return(_)) {
+                break;
+            }
+        }
+        self.scopes.pop();
+        signal
+    }
+
+    fn eval_stmt(&mut self, stmt: &Stmt) -> Signal {
+        match stmt {
+            Stmt::Let { name, ty, init } => {
+                let value = cast_to(self.eval_expr(init), ty);
+                self.scopes.last_mut().unwrap().insert(name.clone(), Rc::new(RefCell::new(value)));
+                Signal::Normal
+            }
+            Stmt::Assign { target, value } => {
+                let new_value = self.eval_expr(value);
+                let cell = self.eval_lvalue(target);
+                let ty = cell.borrow().type_of();
+                *cell.borrow_mut() = cast_to(new_value, &ty);
+                Signal::Normal
+            }
+            Stmt::Expr(e) => {
+                self.eval_expr(e);
+                Signal::Normal
+            }
+            Stmt::Return(value) => {
+                let v = value.as_ref().map(|e| self.eval_expr(e)).unwrap_or(Value::Void);
+                Signal::Return(v)
+            }
+            Stmt::If { cond, then_branch, else_branch } => {
+                if self.eval_expr(cond).as_bool() {
+                    self.eval_block(then_branch)
+                } else if let Some(else_b) = else_branch {
+                    self.eval_block(else_b)
+                } else {
+                    Signal::Normal
+                }
+            }
+            Stmt::While { cond, body } => {
+                while self.eval_expr(cond).as_bool() {
+                    if let Signal::Return(v) = self.eval_block(body) {
+                        return Signal::Return(v);
+                    }
+                }
+                Signal::Normal
+            }
+        }
+    }
+
+    /// Resolves an lvalue expression (identifier or dereference chain)
+    /// to the cell it refers to, so assignment can write through it.
+    fn eval_lvalue(&mut self, expr: &Expr) -> Cell {
+        match expr {
+            Expr::Ident(name) => self.lookup_cell(name),
+            Expr::Unary(UnaryOp::Deref, inner) => match self.eval_expr(inner) {
+                Value::Ptr(cell) => cell,
+                other => panic!("cannot dereference non-pointer value {:?}", other),
+            },
+            other => panic!("not an lvalue: {:?}", other),
+        }
+    }
+
+    fn lookup_cell(&self, name: &str) -> Cell {
+        for scope in self.scopes.iter().rev() {
+            if let Some(cell) = scope.get(name) {
+                return cell.clone();
+            }
+        }
+        // `self.globals` holds plain `Value`s rather than `Cell`s - a
+        // `const` has no storage of its own - so `&GLOBAL_X` wraps a
+        // fresh cell around its current value on the fly. That's enough
+        // to read through; writing through the resulting pointer won't
+        // be visible to later reads of the const by name, same as the
+        // const never having had an address to begin with.
+        if let Some(value) = self.globals.get(name) {
+            return Rc::new(RefCell::new(value.clone()));
+        }
+        panic!("use of undeclared identifier `{}`", name)
+    }
+
+    fn eval_expr(&mut self, expr: &Expr) -> Value {
+        match expr {
+            // Kept as a full-width i64 so a literal like `4000000000`
+            // doesn't lose bits before `cast_to` reinterprets it as the
+            // declaration site's actual type (e.g. `u32`).
+            Expr::IntLit(n) => Value::I64(*n),
+            Expr::FloatLit(n) => Value::F64(*n),
+            Expr::BoolLit(b) => Value::Bool(*b),
+            Expr::Ident(name) => {
+                for scope in self.scopes.iter().rev() {
+                    if let Some(cell) = scope.get(name) {
+                        return cell.borrow().clone();
+                    }
+                }
+                if let Some(v) = self.globals.get(name) {
+                    return v.clone();
+                }
+                if self.functions.contains_key(name) {
+                    return Value::FnPtr(name.clone());
+                }
+                panic!("use of undeclared identifier `{}`", name)
+            }
+            Expr::Unary(op, inner) => self.eval_unary(*op, inner),
+            Expr::Binary(op, lhs, rhs) => self.eval_binary(*op, lhs, rhs),
+            Expr::Call(callee, args) => {
+                if let Expr::Ident(name) = callee.as_ref() {
+                    if let Some(intrinsic) = Intrinsic::from_name(name) {
+                        let arg_values: Vec<Value> = args.iter().map(|a| self.eval_expr(a)).collect();
+                        return self.eval_intrinsic(intrinsic, arg_values);
+                    }
+                }
+                let arg_values: Vec<Value> = args.iter().map(|a| self.eval_expr(a)).collect();
+                match self.eval_expr(callee) {
+                    Value::FnPtr(name) => self.call(&name, arg_values),
+                    other => panic!("cannot call non-function value {:?}", other),
+                }
+            }
+            Expr::Index(base, index) => {
+                let base = self.eval_expr(base);
+                let idx = self.eval_expr(index).as_i64() as usize;
+                match base {
+                    Value::Array(cells) => cells[idx].borrow().clone(),
+                    other => panic!("cannot index non-array value {:?}", other),
+                }
+            }
+            Expr::Cast(inner, target) => {
+                let from_ty = self.eval_expr(inner);
+                self.explicit_cast(from_ty, target)
+            }
+            Expr::EnumVariant(enum_name, variant) => {
+                let info = self.enums.get(enum_name).expect("type-checked");
+                let discriminant = info.variants.iter().position(|v| v == variant).expect("type-checked") as i64;
+                Value::Enum(enum_name.clone(), discriminant)
+            }
+            Expr::ArrayLit(elems) => {
+                let values: Vec<Value> = elems.iter().map(|e| self.eval_expr(e)).collect();
+                let elem_ty = values.iter().map(Value::type_of).fold(None, |acc, ty| match acc {
+                    None => Some(ty),
+                    Some(acc_ty) => Type::promote(&acc_ty, &ty),
+                });
+                let cells = match elem_ty {
+                    Some(ty) => values.into_iter().map(|v| Rc::new(RefCell::new(cast_to(v, &ty)))).collect(),
+                    None => Vec::new(),
+                };
+                Value::Array(Rc::new(cells))
+            }
+        }
+    }
+
+    fn eval_unary(&mut self, op: UnaryOp, inner: &Expr) -> Value {
+        match op {
+            UnaryOp::Ref => Value::Ptr(self.eval_lvalue(inner)),
+            UnaryOp::Deref => match self.eval_expr(inner) {
+                Value::Ptr(cell) => cell.borrow().clone(),
+                other => panic!("cannot dereference non-pointer value {:?}", other),
+            },
+            UnaryOp::Neg => match self.eval_expr(inner) {
+                Value::I32(n) => Value::I32(-n),
+                Value::I64(n) => Value::I64(-n),
+                Value::F32(n) => Value::F32(-n),
+                Value::F64(n) => Value::F64(-n),
+                other => panic!("cannot negate {:?}", other),
+            },
+            UnaryOp::BitNot => match self.eval_expr(inner) {
+                Value::I32(n) => Value::I32(!n),
+                Value::I64(n) => Value::I64(!n),
+                Value::U32(n) => Value::U32(!n),
+                Value::U64(n) => Value::U64(!n),
+                other => panic!("cannot bitwise-not {:?}", other),
+            },
+        }
+    }
+
+    /// Re-derives the statically-checked type of `expr`'s result. Only
+    /// needed for `eval_binary`'s `Shl`/`Shr` arms: an un-cast literal
+    /// like the `1` in `1 << 40` evaluates to a full-width `Value::I64`
+    /// regardless of its actual (narrower) static type - see the comment
+    /// on `eval_expr`'s own `IntLit` arm - so masking the shift amount
+    /// off that runtime `Value`'s variant masks to the wrong bit width.
+    /// This mirrors enough of `TypeChecker::type_of_expr` to answer that
+    /// one question without wiring the whole type-checker through the
+    /// evaluator.
+    fn static_operand_type(&self, expr: &Expr) -> Type {
+        match expr {
+            Expr::IntLit(_) => Type::I32,
+            Expr::FloatLit(_) => Type::F32,
+            Expr::BoolLit(_) => Type::Bool,
+            Expr::Ident(name) => {
+                for scope in self.scopes.iter().rev() {
+                    if let Some(cell) = scope.get(name) {
+                        return cell.borrow().type_of();
+                    }
+                }
+                self.globals.get(name).map(Value::type_of).unwrap_or(Type::I32)
+            }
+            Expr::Unary(UnaryOp::Ref, inner) => Type::Pointer(Box::new(self.static_operand_type(inner))),
+            Expr::Unary(UnaryOp::Deref, inner) => match self.static_operand_type(inner) {
+                Type::Pointer(t) => *t,
+                other => other,
+            },
+            Expr::Unary(UnaryOp::Neg | UnaryOp::BitNot, inner) => self.static_operand_type(inner),
+            Expr::Binary(op, _, _) if op.is_comparison() => Type::Bool,
+            Expr::Binary(BinOp::Shl | BinOp::Shr, lhs, _) => self.static_operand_type(lhs),
+            Expr::Binary(_, lhs, rhs) => {
+                let l = self.static_operand_type(lhs);
+                let r = self.static_operand_type(rhs);
+                Type::promote(&l, &r).unwrap_or(l)
+            }
+            Expr::Cast(_, target) => target.clone(),
+            Expr::EnumVariant(name, _) => Type::Enum(name.clone()),
+            Expr::Index(base, _) => match self.static_operand_type(base) {
+                Type::Array(elem, _) => *elem,
+                other => other,
+            },
+            Expr::Call(callee, _) => match callee.as_ref() {
+                Expr::Ident(name) => self.functions.get(name).map(|f| f.ret.clone()).unwrap_or(Type::I32),
+                _ => Type::I32,
+            },
+            Expr::ArrayLit(_) => Type::Void,
+        }
+    }
+
+    fn eval_binary(&mut self, op: BinOp, lhs_expr: &Expr, rhs: &Expr) -> Value {
+        let lhs = self.eval_expr(lhs_expr);
+        let rhs = self.eval_expr(rhs);
+
+        if op.is_comparison() {
+            let (l, r, _) = promote_pair(lhs, rhs);
+            let ordering = match (&l, &r) {
+                (Value::F32(a), Value::F32(b)) => a.partial_cmp(b),
+                (Value::F64(a), Value::F64(b)) => a.partial_cmp(b),
+                _ => Some(l.as_i64().cmp(&r.as_i64())),
+            };
+            let ordering = ordering.expect("NaN comparison");
+            return Value::Bool(match op {
+                BinOp::Lt => ordering.is_lt(),
+                BinOp::Gt => ordering.is_gt(),
+                BinOp::Le => ordering.is_le(),
+                BinOp::Ge => ordering.is_ge(),
+                BinOp::Eq => ordering.is_eq(),
+                BinOp::Ne => !ordering.is_eq(),
+                _ => unreachable!(),
+            });
+        }
+
+        match op {
+            // The shift amount does not get promoted with the left-hand
+            // side; only its numeric value (masked to the operand's bit
+            // width) matters. `wrapping_shl`/`wrapping_shr` already mask
+            // the amount for us, and `wrapping_shr` on a signed integer
+            // performs an arithmetic (sign-filling) shift while on an
+            // unsigned integer it performs a logical (zero-filling) one
+            // - exactly the SRA/SRL split the language needs.
+            BinOp::Shl => {
+                let amt = rhs.as_i64() as u32;
+                // An un-narrowed literal lhs (e.g. the `1` in `1 << 40`)
+                // still carries the full-width `Value::I64` it was
+                // evaluated as, not its statically-checked type, so
+                // masking off its runtime variant would mask to the
+                // wrong bit width. Cast to the static type first.
+                let lhs = cast_to(lhs, &self.static_operand_type(lhs_expr));
+                match lhs {
+                    Value::I32(n) => Value::I32(n.wrapping_shl(amt)),
+                    Value::U32(n) => Value::U32(n.wrapping_shl(amt)),
+                    Value::I64(n) => Value::I64(n.wrapping_shl(amt)),
+                    Value::U64(n) => Value::U64(n.wrapping_shl(amt)),
+                    other => panic!("cannot shift {:?}", other),
+                }
+            }
+            BinOp::Shr => {
+                let amt = rhs.as_i64() as u32;
+                let lhs = cast_to(lhs, &self.static_operand_type(lhs_expr));
+                match lhs {
+                    Value::I32(n) => Value::I32(n.wrapping_shr(amt)),
+                    Value::U32(n) => Value::U32(n.wrapping_shr(amt)),
+                    Value::I64(n) => Value::I64(n.wrapping_shr(amt)),
+                    Value::U64(n) => Value::U64(n.wrapping_shr(amt)),
+                    other => panic!("cannot shift {:?}", other),
+                }
+            }
+            BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor => {
+                let (l, r, ty) = promote_pair(lhs, rhs);
+                let (a, b) = (l.as_i64(), r.as_i64());
+                let result = match op {
+                    BinOp::BitAnd => a & b,
+                    BinOp::BitOr => a | b,
+                    BinOp::BitXor => a ^ b,
+                    _ => unreachable!(),
+                };
+                cast_to(Value::I64(result), &ty)
+            }
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem => {
+                let (l, r, ty) = promote_pair(lhs, rhs);
+                if ty.is_float() {
+                    let (a, b) = (l.as_f64(), r.as_f64());
+                    let result = match op {
+                        BinOp::Add => a + b,
+                        BinOp::Sub => a - b,
+                        BinOp::Mul => a * b,
+                        BinOp::Div => a / b,
+                        BinOp::Rem => a % b,
+                        _ => unreachable!(),
+                    };
+                    cast_to(Value::F64(result), &ty)
+                } else {
+                    let (a, b) = (l.as_i64(), r.as_i64());
+                    let result = match op {
+                        BinOp::Add => a.wrapping_add(b),
+                        BinOp::Sub => a.wrapping_sub(b),
+                        BinOp::Mul => a.wrapping_mul(b),
+                        BinOp::Div => a / b,
+                        BinOp::Rem => a % b,
+                        _ => unreachable!(),
+                    };
+                    cast_to(Value::I64(result), &ty)
+                }
+            }
+            _ => unreachable!("comparisons handled above"),
+        }
+    }
+
+    /// Dispatches a call to one of the math intrinsics, lowering straight
+    /// to the host libm equivalent (`f32`/`f64` already wrap it via
+    /// `std`). Constant arguments such as `GLOBAL_PI` need no extra
+    /// folding here - consts are evaluated once in `Interpreter::new`,
+    /// so the call already receives a plain value.
+    fn eval_intrinsic(&mut self, intrinsic: Intrinsic, args: Vec<Value>) -> Value {
+        let arg_types: Vec<Type> = args.iter().map(Value::type_of).collect();
+
+        if intrinsic == Intrinsic::Scalbn {
+            let ty = intrinsics::float_operand_type(&arg_types[..1]).expect("type-checked");
+            let x = cast_to(args[0].clone(), &ty);
+            let n = args[1].as_i64() as i32;
+            return match x {
+                Value::F32(x) => Value::F32(x * 2f32.powi(n)),
+                Value::F64(x) => Value::F64(x * 2f64.powi(n)),
+                _ => unreachable!(),
+            };
+        }
+
+        let ty = intrinsics::float_operand_type(&arg_types).expect("type-checked");
+        match intrinsic {
+            Intrinsic::Sqrt => apply_unary_float(cast_to(args[0].clone(), &ty), f32::sqrt, f64::sqrt),
+            Intrinsic::Sin => apply_unary_float(cast_to(args[0].clone(), &ty), f32::sin, f64::sin),
+            Intrinsic::Cos => apply_unary_float(cast_to(args[0].clone(), &ty), f32::cos, f64::cos),
+            Intrinsic::Floor => apply_unary_float(cast_to(args[0].clone(), &ty), f32::floor, f64::floor),
+            Intrinsic::Copysign => {
+                let x = cast_to(args[0].clone(), &ty);
+                let y = cast_to(args[1].clone(), &ty);
+                match (x, y) {
+                    (Value::F32(x), Value::F32(y)) => Value::F32(x.copysign(y)),
+                    (Value::F64(x), Value::F64(y)) => Value::F64(x.copysign(y)),
+                    _ => unreachable!(),
+                }
+            }
+            Intrinsic::Scalbn => unreachable!("handled above"),
+        }
+    }
+
+    /// Implements `expr as T`: the full matrix the type-checker's
+    /// `Type::castable_to` allows - integer truncation/widening with the
+    /// source's own signedness (Rust's numeric `as` already sign-extends
+    /// from a signed source and zero-extends from an unsigned one, and
+    /// truncates to the low bits on narrowing, which is exactly what we want),
+    /// float<->int with round-to-nearest instead of Rust's default
+    /// truncate-toward-zero, and pointer<->integer reinterpretation via
+    /// `addr_table`.
+    fn explicit_cast(&mut self, v: Value, to: &Type) -> Value {
+        if v.type_of() == *to {
+            return v;
+        }
+        match (&v, to) {
+            (Value::F32(_) | Value::F64(_), t) if t.is_integer() => {
+                let f = v.as_f64().round();
+                match t {
+                    Type::I32 => Value::I32(saturate(f, i32::MIN as f64, i32::MAX as f64) as i32),
+                    Type::U32 => Value::U32(saturate(f, u32::MIN as f64, u32::MAX as f64) as u32),
+                    Type::I64 => Value::I64(saturate(f, i64::MIN as f64, i64::MAX as f64) as i64),
+                    Type::U64 => Value::U64(saturate(f, u64::MIN as f64, u64::MAX as f64) as u64),
+                    _ => unreachable!(),
+                }
+            }
+            (Value::I32(n), Type::F32) => Value::F32(*n as f32),
+            (Value::I32(n), Type::F64) => Value::F64(*n as f64),
+            (Value::I64(n), Type::F32) => Value::F32(*n as f32),
+            (Value::I64(n), Type::F64) => Value::F64(*n as f64),
+            (Value::U32(n), Type::F32) => Value::F32(*n as f32),
+            (Value::U32(n), Type::F64) => Value::F64(*n as f64),
+            (Value::U64(n), Type::F32) => Value::F32(*n as f32),
+            (Value::U64(n), Type::F64) => Value::F64(*n as f64),
+            (Value::F32(n), Type::F64) => Value::F64(*n as f64),
+            (Value::F64(n), Type::F32) => Value::F32(*n as f32),
+
+            // Integer <-> integer: match on the source's own signedness
+            // so widening sign-extends or zero-extends correctly, then
+            // let Rust's `as` truncate on narrowing.
+            (Value::I32(n), Type::I64) => Value::I64(*n as i64),
+            (Value::I32(n), Type::U32) => Value::U32(*n as u32),
+            (Value::I32(n), Type::U64) => Value::U64(*n as i64 as u64),
+            (Value::I64(n), Type::I32) => Value::I32(*n as i32),
+            (Value::I64(n), Type::U32) => Value::U32(*n as u32),
+            (Value::I64(n), Type::U64) => Value::U64(*n as u64),
+            (Value::U32(n), Type::I32) => Value::I32(*n as i32),
+            (Value::U32(n), Type::I64) => Value::I64(*n as i64),
+            (Value::U32(n), Type::U64) => Value::U64(*n as u64),
+            (Value::U64(n), Type::I32) => Value::I32(*n as i32),
+            (Value::U64(n), Type::I64) => Value::I64(*n as i64),
+            (Value::U64(n), Type::U32) => Value::U32(*n as u32),
+
+            (Value::Ptr(cell), t) if t.is_integer() => {
+                let addr = Rc::as_ptr(cell) as usize;
+                self.addr_table.insert(addr, cell.clone());
+                self.explicit_cast(Value::U64(addr as u64), t)
+            }
+            (v, Type::Pointer(_)) if v.type_of().is_integer() => {
+                let addr = self.explicit_cast(v.clone(), &Type::U64);
+                let addr = match addr {
+                    Value::U64(n) => n as usize,
+                    _ => unreachable!(),
+                };
+                match self.addr_table.get(&addr) {
+                    Some(cell) => Value::Ptr(cell.clone()),
+                    None => panic!("cast of integer {} to a pointer does not name a live value", addr),
+                }
+            }
+            (Value::Ptr(cell), Type::Pointer(_)) => Value::Ptr(cell.clone()),
+
+            // `enum as underlying`: the type-checker's `enum_info` check
+            // already restricted `t` to the enum's declared underlying
+            // type, so this just reads the discriminant out at that type.
+            (Value::Enum(_, d), t) if t.is_integer() => cast_to(Value::I64(*d), t),
+
+            (other, t) => panic!("unsupported cast from {:?} to {}", other, t),
+        }
+    }
+}
+
+fn apply_unary_float(v: Value, f32_op: fn(f32) -> f32, f64_op: fn(f64) -> f64) -> Value {
+    match v {
+        Value::F32(x) => Value::F32(f32_op(x)),
+        Value::F64(x) => Value::F64(f64_op(x)),
+        other => unreachable!("expected float, found {:?}", other),
+    }
+}
+
+fn saturate(v: f64, min: f64, max: f64) -> f64 {
+    if v.is_nan() {
+        0.0
+    } else {
+        v.clamp(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::typeck::TypeChecker;
+
+    /// Runs each line as a REPL entry against one shared session and
+    /// returns the value of the last one, which must be a bare
+    /// expression statement.
+    fn eval_lines(lines: &[&str]) -> Value {
+        let mut checker = TypeChecker::new_repl();
+        let mut interp = Interpreter::new_repl();
+        let mut result = None;
+        for line in lines {
+            let tokens = Lexer::new(line).tokenize();
+            match Parser::new(tokens).parse_repl_entry() {
+                ReplEntry::Item(item) => {
+                    checker.declare_item(&item).expect("type error");
+                    interp.declare_item(&item);
+                }
+                ReplEntry::Stmt(stmt) => {
+                    checker.check_repl_stmt(&stmt).expect("type error");
+                    result = interp.eval_repl_stmt(&stmt);
+                }
+            }
+        }
+        result.expect("final line must be an expression statement")
+    }
+
+    #[test]
+    fn shift_amount_masks_to_the_static_width_of_an_unnarrowed_literal() {
+        // No intervening `let` to force the `1` through `cast_to`: it
+        // stays a full-width i64 until the declaration site narrows it,
+        // so the shift mask has to come from the static i32 type, not
+        // the literal's runtime representation.
+        assert_eq!(eval_lines(&["x: i32 = 1 << 40;", "x;"]), Value::I32(256));
+    }
+
+    #[test]
+    fn shift_amount_masks_the_same_once_the_operand_is_already_narrowed() {
+        assert_eq!(eval_lines(&["y: i32 = 1;", "z: i32 = y << 40;", "z;"]), Value::I32(256));
+    }
+
+    #[test]
+    fn right_shift_sign_extends_on_signed_operands() {
+        assert_eq!(eval_lines(&["neg: i32 = -8;", "neg >> 1;"]), Value::I32(-4));
+    }
+
+    #[test]
+    fn right_shift_zero_fills_on_unsigned_operands() {
+        assert_eq!(eval_lines(&["pos: u32 = 4000000000;", "pos >> 1;"]), Value::U32(2000000000));
+    }
+
+    #[test]
+    fn explicit_cast_truncates_to_the_target_width() {
+        assert_eq!(eval_lines(&["big: i64 = 4294967296;", "big as i32;"]), Value::I32(0));
+    }
+
+    #[test]
+    fn explicit_cast_sign_extends_rather_than_reinterpreting_bits() {
+        assert_eq!(eval_lines(&["neg: i32 = -1;", "neg as i64;"]), Value::I64(-1));
+    }
+
+    #[test]
+    fn explicit_cast_from_float_rounds_to_nearest() {
+        assert_eq!(eval_lines(&["pi: f64 = 3.14159;", "pi as i32;"]), Value::I32(3));
+    }
+
+    #[test]
+    fn explicit_cast_from_out_of_range_float_saturates() {
+        assert_eq!(eval_lines(&["huge: f64 = 1.0e300;", "huge as i32;"]), Value::I32(i32::MAX));
+    }
+
+    #[test]
+    fn sqrt_intrinsic_promotes_an_integer_argument_to_f64() {
+        assert_eq!(eval_lines(&["sqrt(4);"]), Value::F64(2.0));
+    }
+
+    #[test]
+    fn cos_intrinsic_stays_f32_when_the_argument_is_already_f32() {
+        assert_eq!(eval_lines(&["angle: f32 = 0.0;", "cos(angle);"]), Value::F32(1.0));
+    }
+
+    #[test]
+    fn copysign_intrinsic_takes_the_sign_of_its_second_argument() {
+        assert_eq!(eval_lines(&["copysign(5.0, -1.0);"]), Value::F64(-5.0));
+    }
+
+    #[test]
+    fn scalbn_intrinsic_scales_by_a_power_of_two() {
+        assert_eq!(eval_lines(&["scalbn(1.5, 1);"]), Value::F64(3.0));
+    }
+
+    #[test]
+    fn enum_variant_casts_to_its_declaration_order_discriminant() {
+        let v = eval_lines(&["enum Direction { North, East, South, West }", "d: Direction = Direction::South;", "d as i32;"]);
+        assert_eq!(v, Value::I32(2));
+    }
+
+    #[test]
+    fn two_variant_enum_is_usable_directly_as_a_bool_condition() {
+        let v = eval_lines(&["enum Toggle { Off, On }", "t: Toggle = Toggle::On;", "f: i32 = 0;", "if (t) { f = 1; }", "f;"]);
+        assert_eq!(v, Value::I32(1));
+    }
+
+    #[test]
+    fn assigning_to_a_const_is_rejected_at_type_check_time() {
+        let mut checker = TypeChecker::new_repl();
+        let mut interp = Interpreter::new_repl();
+
+        let decl = Parser::new(Lexer::new("const GLOBAL_X: i32 = 10;").tokenize()).parse_repl_entry();
+        let ReplEntry::Item(item) = decl else { panic!("expected an item") };
+        checker.declare_item(&item).expect("const declares fine");
+        interp.declare_item(&item);
+
+        let assign = Parser::new(Lexer::new("GLOBAL_X = 99;").tokenize()).parse_repl_entry();
+        let ReplEntry::Stmt(stmt) = assign else { panic!("expected a statement") };
+        assert!(checker.check_repl_stmt(&stmt).is_err(), "assigning to a const should be a type error");
+    }
+
+    #[test]
+    fn equality_between_variants_of_the_same_enum() {
+        let v = eval_lines(&[
+            "enum Direction { North, East, South, West }",
+            "d: Direction = Direction::South;",
+            "d == Direction::West;",
+        ]);
+        assert_eq!(v, Value::Bool(false));
+    }
+
+    #[test]
+    fn ordering_enum_values_is_a_type_error() {
+        let mut checker = TypeChecker::new_repl();
+        let decl = Parser::new(Lexer::new("enum Direction { North, East, South, West }").tokenize()).parse_repl_entry();
+        let ReplEntry::Item(item) = decl else { panic!("expected an item") };
+        checker.declare_item(&item).expect("enum declares fine");
+
+        let cmp = Parser::new(Lexer::new("Direction::North < Direction::South;").tokenize()).parse_repl_entry();
+        let ReplEntry::Stmt(stmt) = cmp else { panic!("expected a statement") };
+        assert!(checker.check_repl_stmt(&stmt).is_err(), "ordering two enum values should be a type error");
+    }
+}
@@ -0,0 +1,56 @@
+//! Built-in math functions. These are resolved by name instead of being
+//! declared as ordinary `fn` items, so they need no entry in the global
+//! symbol table and can't be shadowed or redefined by user code.
+
+use crate::types::Type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intrinsic {
+    Sqrt,
+    Sin,
+    Cos,
+    Floor,
+    Copysign,
+    Scalbn,
+}
+
+impl Intrinsic {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sqrt" => Intrinsic::Sqrt,
+            "sin" => Intrinsic::Sin,
+            "cos" => Intrinsic::Cos,
+            "floor" => Intrinsic::Floor,
+            "copysign" => Intrinsic::Copysign,
+            "scalbn" => Intrinsic::Scalbn,
+            _ => return None,
+        })
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            Intrinsic::Copysign | Intrinsic::Scalbn => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// The float type intrinsic arguments are evaluated in: same-type
+/// operands keep their width (f32 stays f32), but since these functions
+/// only have f32->f32 / f64->f64 signatures, an integer argument (e.g.
+/// the literal in `sqrt(4)`) has no float width of its own to keep and
+/// defaults to f64 - the same default `test_promotions`-style code uses
+/// wherever a float context meets a bare integer literal.
+pub fn float_operand_type(arg_types: &[Type]) -> Option<Type> {
+    let mut common = arg_types.first()?.clone();
+    for ty in &arg_types[1..] {
+        common = Type::promote(&common, ty)?;
+    }
+    Some(if common.is_float() {
+        common
+    } else if common.is_integer() {
+        Type::F64
+    } else {
+        return None;
+    })
+}
@@ -0,0 +1,844 @@
+//! A compact register-machine IR sitting between the AST and the native
+//! (tree-walking) backend in `eval`. It gives constant folding a flat
+//! list of ops to work over instead of a tree, and a place to execute a
+//! function without going through `eval`'s scope-stack model at all.
+//! Folding happens once, up front, in `const_eval` - a `+`/`-`/`*` or
+//! negation subexpression built entirely from literals and known
+//! `const`s of one kind, all-int or all-float (e.g. `GLOBAL_X * 2`),
+//! collapses to a single `LoadConst` before any other lowering runs, so
+//! there's no later peephole pass chasing constants through emitted ops.
+//! Other operators and mixed int/float operands are not folded.
+//!
+//! This is deliberately narrower than the full language: it lowers the
+//! straight-line arithmetic, comparisons, calls, and structured
+//! `if`/`while` control flow that functions like `fib` and
+//! `test_logic` are built from. Pointers, casts, and intrinsics don't
+//! have a register-machine encoding yet and are rejected at lowering
+//! time with a clear message rather than silently miscompiled.
+//!
+//! Arrays (`test_arrays`'s N-dimensional case included) lower to flat
+//! address arithmetic over a per-call `mem` arena rather than to nested
+//! `Value::Array`s: a `let` bound to an array literal allocates one
+//! contiguous block sized to the literal's flattened element count, and
+//! indexing multiplies each index by its dimension's stride (row-major,
+//! like a C array) and sums the results into a single offset. Only
+//! array locals initialized directly from a (possibly nested) array
+//! literal are supported - an array built any other way (a parameter, a
+//! literal assigned conditionally, one copied from another array) has
+//! no static shape to compute strides from and is rejected the same way
+//! pointers and casts are.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::ast::{self, BinOp, UnaryOp};
+use crate::error::{TypeError, TypeResult};
+
+pub type Reg = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IrValue {
+    Int(i64),
+    Float(f64),
+}
+
+impl IrValue {
+    fn as_i64(self) -> i64 {
+        match self {
+            IrValue::Int(n) => n,
+            IrValue::Float(_) => panic!("expected int IR value"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone)]
+pub enum Op {
+    LoadConst(Reg, usize),
+    Move(Reg, Reg),
+    AddConst(Reg, Reg, i64),
+    MulConst(Reg, Reg, i64),
+    Add(Reg, Reg, Reg),
+    Sub(Reg, Reg, Reg),
+    Mul(Reg, Reg, Reg),
+    Cmp(Reg, Reg, Reg, CmpOp),
+    Jump(usize),
+    BranchIfFalse(Reg, usize),
+    Call(Reg, String, Vec<Reg>),
+    Return(Option<Reg>),
+    /// Reserves `size` consecutive cells in the function's `mem` arena
+    /// and puts the base address of the block into `Reg`.
+    Alloc(Reg, usize),
+    /// `mem[regs[base] + regs[offset]] = regs[src]`.
+    Store(Reg, Reg, Reg),
+    /// `Reg = mem[regs[base] + regs[offset]]`.
+    Load(Reg, Reg, Reg),
+}
+
+#[derive(Debug, Clone)]
+pub struct IrFunction {
+    pub name: String,
+    pub num_params: usize,
+    pub num_regs: u32,
+    pub consts: Vec<IrValue>,
+    pub ops: Vec<Op>,
+}
+
+/// Lowers every function in `program` to IR. Functions that use
+/// constructs this backend doesn't model (see module docs) are skipped
+/// rather than failing the whole program, since the native backend in
+/// `eval` still handles them - but the skip is logged to stderr with the
+/// lowering error, so a function silently missing from the result isn't
+/// mistaken for one that lowered fine.
+pub fn lower_program(program: &ast::Program) -> Vec<IrFunction> {
+    let globals = collect_const_globals(program);
+    let mut out = Vec::new();
+    for item in &program.items {
+        if let ast::Item::Function(f) = item {
+            match lower_function(f, &globals) {
+                Ok(ir_fn) => out.push(ir_fn),
+                Err(e) => eprintln!("ir: skipping `{}`, not lowerable: {}", f.name, e),
+            }
+        }
+    }
+    out
+}
+
+fn collect_const_globals(program: &ast::Program) -> HashMap<String, IrValue> {
+    let mut globals = HashMap::new();
+    for item in &program.items {
+        if let ast::Item::Const(c) = item {
+            if let Some(v) = const_eval(&c.value, &globals) {
+                globals.insert(c.name.clone(), v);
+            }
+        }
+    }
+    globals
+}
+
+/// Evaluates an expression at compile time if it's built entirely from
+/// literals and already-known constants combined with `+`, `-`, `*`
+/// (both operands int or both float) or unary `-`; anything else
+/// returns `None`. Used both to resolve global `const` references
+/// during lowering and to fold fully-constant subexpressions like
+/// `GLOBAL_X * 2` into a single `LoadConst`.
+fn const_eval(expr: &ast::Expr, globals: &HashMap<String, IrValue>) -> Option<IrValue> {
+    match expr {
+        ast::Expr::IntLit(n) => Some(IrValue::Int(*n)),
+        ast::Expr::FloatLit(n) => Some(IrValue::Float(*n)),
+        ast::Expr::Ident(name) => globals.get(name).copied(),
+        ast::Expr::Unary(UnaryOp::Neg, inner) => match const_eval(inner, globals)? {
+            IrValue::Int(n) => Some(IrValue::Int(n.wrapping_neg())),
+            IrValue::Float(n) => Some(IrValue::Float(-n)),
+        },
+        ast::Expr::Binary(op, lhs, rhs) => {
+            let l = const_eval(lhs, globals)?;
+            let r = const_eval(rhs, globals)?;
+            match (l, r) {
+                (IrValue::Int(a), IrValue::Int(b)) => Some(IrValue::Int(match op {
+                    BinOp::Add => a.wrapping_add(b),
+                    BinOp::Sub => a.wrapping_sub(b),
+                    BinOp::Mul => a.wrapping_mul(b),
+                    _ => return None,
+                })),
+                (IrValue::Float(a), IrValue::Float(b)) => Some(IrValue::Float(match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    _ => return None,
+                })),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// What a name in scope refers to: an ordinary register, or the base
+/// address and static shape of an array allocated in the `mem` arena.
+#[derive(Debug, Clone)]
+enum Binding {
+    Scalar(Reg),
+    Array { base: Reg, dims: Vec<usize> },
+}
+
+/// The dimensions a (possibly nested) array literal implies, outermost
+/// first - e.g. `{{1,2,3},{4,5,6}}` is `[2, 3]`. `None` if any element
+/// isn't itself a literal with a uniform shape, which is as far as this
+/// backend's static address arithmetic can reach.
+fn array_shape(elems: &[ast::Expr]) -> Option<Vec<usize>> {
+    match elems.first() {
+        None => Some(vec![0]),
+        Some(ast::Expr::ArrayLit(first)) => {
+            let inner = array_shape(first)?;
+            for elem in &elems[1..] {
+                let ast::Expr::ArrayLit(other) = elem else { return None };
+                if array_shape(other)? != inner {
+                    return None;
+                }
+            }
+            let mut dims = vec![elems.len()];
+            dims.extend(inner);
+            Some(dims)
+        }
+        Some(_) => {
+            if elems.iter().any(|e| matches!(e, ast::Expr::ArrayLit(_))) {
+                return None;
+            }
+            Some(vec![elems.len()])
+        }
+    }
+}
+
+/// Collects an array literal's leaf (non-`ArrayLit`) expressions in
+/// row-major order, matching the linear layout `array_shape` computes
+/// strides for.
+fn flatten_array_lit<'e>(elems: &'e [ast::Expr], out: &mut Vec<&'e ast::Expr>) {
+    for elem in elems {
+        match elem {
+            ast::Expr::ArrayLit(inner) => flatten_array_lit(inner, out),
+            other => out.push(other),
+        }
+    }
+}
+
+/// Unwraps a chain of `Index` nodes back to the identifier it indexes
+/// and the index expressions in outer-to-inner order, e.g.
+/// `cube[1][0][1]` becomes `("cube", [1, 0, 1])`.
+fn flatten_index_chain(expr: &ast::Expr) -> Option<(&str, Vec<&ast::Expr>)> {
+    match expr {
+        ast::Expr::Ident(name) => Some((name.as_str(), Vec::new())),
+        ast::Expr::Index(base, index) => {
+            let (name, mut indices) = flatten_index_chain(base)?;
+            indices.push(index);
+            Some((name, indices))
+        }
+        _ => None,
+    }
+}
+
+struct Lowerer<'a> {
+    globals: &'a HashMap<String, IrValue>,
+    // A stack mirroring `eval::Interpreter`'s `scopes`: a `let` binds in
+    // the innermost scope, and a nested block that shadows an outer name
+    // gets its own register rather than overwriting the outer binding's
+    // entry - the flat single-map version of this let a shadowing `let`
+    // in one branch of an `if` alias the outer variable's register, so a
+    // path that never entered that branch could still observe the
+    // branch's write.
+    scopes: Vec<HashMap<String, Binding>>,
+    consts: Vec<IrValue>,
+    ops: Vec<Op>,
+    next_reg: Reg,
+}
+
+impl<'a> Lowerer<'a> {
+    fn alloc_reg(&mut self) -> Reg {
+        let r = self.next_reg;
+        self.next_reg += 1;
+        r
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: &str, binding: Binding) {
+        self.scopes.last_mut().unwrap().insert(name.to_string(), binding);
+    }
+
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    fn lookup_scalar(&self, name: &str) -> TypeResult<Reg> {
+        match self.lookup(name) {
+            Some(Binding::Scalar(r)) => Ok(r),
+            Some(Binding::Array { .. }) => Err(TypeError(format!("IR lowering: `{}` is an array, not a plain value", name))),
+            None => Err(TypeError(format!("IR lowering: unbound identifier `{}`", name))),
+        }
+    }
+
+    fn load_const(&mut self, v: IrValue) -> Reg {
+        let idx = self.consts.len();
+        self.consts.push(v);
+        let dst = self.alloc_reg();
+        self.ops.push(Op::LoadConst(dst, idx));
+        dst
+    }
+
+    fn lower_expr(&mut self, expr: &ast::Expr) -> TypeResult<Reg> {
+        if let Some(v) = const_eval(expr, self.globals) {
+            return Ok(self.load_const(v));
+        }
+        match expr {
+            ast::Expr::IntLit(n) => Ok(self.load_const(IrValue::Int(*n))),
+            ast::Expr::FloatLit(n) => Ok(self.load_const(IrValue::Float(*n))),
+            ast::Expr::Ident(name) => self.lookup_scalar(name),
+            ast::Expr::Binary(op, lhs, rhs) => self.lower_binary(*op, lhs, rhs),
+            // No dedicated negate op: `-x` is `x * -1`, which `MulConst`
+            // already covers for both int and float registers.
+            ast::Expr::Unary(UnaryOp::Neg, inner) => {
+                let src = self.lower_expr(inner)?;
+                let dst = self.alloc_reg();
+                self.ops.push(Op::MulConst(dst, src, -1));
+                Ok(dst)
+            }
+            ast::Expr::Call(callee, args) => {
+                // Only a direct call to a name that isn't itself a
+                // local binding counts as a static call target: `op(a,
+                // b)` where `op: fn(i32, i32) -> i32` is a parameter
+                // holding a function pointer has no register-operand
+                // call op in this IR yet, so it's rejected here rather
+                // than being mis-lowered into a call to a function
+                // literally named "op".
+                let name = match callee.as_ref() {
+                    ast::Expr::Ident(name) if crate::intrinsics::Intrinsic::from_name(name).is_some() => {
+                        return Err(TypeError(format!("IR lowering: intrinsic `{}` is not supported", name)))
+                    }
+                    ast::Expr::Ident(name) if self.lookup(name).is_none() => name.clone(),
+                    ast::Expr::Ident(name) => {
+                        return Err(TypeError(format!("IR lowering: indirect call through `{}` is not supported", name)))
+                    }
+                    _ => return Err(TypeError("IR lowering: only direct calls by name are supported".into())),
+                };
+                let arg_regs = args.iter().map(|a| self.lower_expr(a)).collect::<TypeResult<Vec<_>>>()?;
+                let dst = self.alloc_reg();
+                self.ops.push(Op::Call(dst, name, arg_regs));
+                Ok(dst)
+            }
+            ast::Expr::Index(_, _) => {
+                let (base, addr) = self.lower_array_address(expr)?;
+                let dst = self.alloc_reg();
+                self.ops.push(Op::Load(dst, base, addr));
+                Ok(dst)
+            }
+            other => Err(TypeError(format!("IR lowering: unsupported expression {:?}", other))),
+        }
+    }
+
+    /// Allocates a `mem` block for an array literal and stores each of
+    /// its (flattened, row-major) leaf elements into it, returning the
+    /// shape the literal's nesting implies - e.g. `{{1,2,3},{4,5,6}}` is
+    /// shape `[2, 3]`.
+    fn lower_array_literal(&mut self, elems: &[ast::Expr]) -> TypeResult<(Reg, Vec<usize>)> {
+        let dims = array_shape(elems).ok_or_else(|| TypeError("IR lowering: array literal has no static shape".into()))?;
+        let total: usize = dims.iter().product();
+        let base = self.alloc_reg();
+        self.ops.push(Op::Alloc(base, total));
+        let mut leaves = Vec::new();
+        flatten_array_lit(elems, &mut leaves);
+        for (i, leaf) in leaves.into_iter().enumerate() {
+            let value_reg = self.lower_expr(leaf)?;
+            let offset_reg = self.load_const(IrValue::Int(i as i64));
+            self.ops.push(Op::Store(base, offset_reg, value_reg));
+        }
+        Ok((base, dims))
+    }
+
+    /// Resolves an `a[i][j]...` chain to the `(base, address)` register
+    /// pair `Load`/`Store` need: `base` is the array's `mem` block, and
+    /// `address` is the already-computed `idx0*stride0 + idx1*stride1 +
+    /// ...` offset into it (row-major, like a C array).
+    fn lower_array_address(&mut self, expr: &ast::Expr) -> TypeResult<(Reg, Reg)> {
+        let (name, indices) = flatten_index_chain(expr)
+            .ok_or_else(|| TypeError(format!("IR lowering: unsupported indexing expression {:?}", expr)))?;
+        let (base, dims) = match self.lookup(name) {
+            Some(Binding::Array { base, dims }) => (base, dims),
+            Some(Binding::Scalar(_)) => return Err(TypeError(format!("IR lowering: `{}` is not an array", name))),
+            None => return Err(TypeError(format!("IR lowering: unbound identifier `{}`", name))),
+        };
+        if indices.len() != dims.len() {
+            return Err(TypeError(format!(
+                "IR lowering: `{}` is {}-dimensional, found {} index expressions",
+                name,
+                dims.len(),
+                indices.len()
+            )));
+        }
+        let strides: Vec<usize> = (0..dims.len()).map(|k| dims[k + 1..].iter().product()).collect();
+        let mut addr: Option<Reg> = None;
+        for (idx_expr, stride) in indices.iter().zip(strides) {
+            let idx_reg = self.lower_expr(idx_expr)?;
+            let term = if stride == 1 {
+                idx_reg
+            } else {
+                let scaled = self.alloc_reg();
+                self.ops.push(Op::MulConst(scaled, idx_reg, stride as i64));
+                scaled
+            };
+            addr = Some(match addr {
+                None => term,
+                Some(acc) => {
+                    let sum = self.alloc_reg();
+                    self.ops.push(Op::Add(sum, acc, term));
+                    sum
+                }
+            });
+        }
+        // A 0-dimensional index chain can't occur (this is only called
+        // on an `Index` node, so flatten_index_chain returns
+        // indices.len() >= 1), but an explicit zero offset
+        // keeps this total instead of leaning on that invariant.
+        let addr = match addr {
+            Some(r) => r,
+            None => self.load_const(IrValue::Int(0)),
+        };
+        Ok((base, addr))
+    }
+
+    fn lower_binary(&mut self, op: BinOp, lhs: &ast::Expr, rhs: &ast::Expr) -> TypeResult<Reg> {
+        // Fast paths straight out of the request: `reg OP literal`
+        // lowers to the dedicated immediate-operand ops instead of
+        // spending a register and a LoadConst on the literal.
+        if let ast::Expr::IntLit(n) = rhs {
+            if matches!(op, BinOp::Add | BinOp::Mul) {
+                let lhs_reg = self.lower_expr(lhs)?;
+                let dst = self.alloc_reg();
+                self.ops.push(if op == BinOp::Add { Op::AddConst(dst, lhs_reg, *n) } else { Op::MulConst(dst, lhs_reg, *n) });
+                return Ok(dst);
+            }
+        }
+
+        let lhs_reg = self.lower_expr(lhs)?;
+        let rhs_reg = self.lower_expr(rhs)?;
+        let dst = self.alloc_reg();
+        let cmp = match op {
+            BinOp::Lt => Some(CmpOp::Lt),
+            BinOp::Gt => Some(CmpOp::Gt),
+            BinOp::Le => Some(CmpOp::Le),
+            BinOp::Ge => Some(CmpOp::Ge),
+            BinOp::Eq => Some(CmpOp::Eq),
+            BinOp::Ne => Some(CmpOp::Ne),
+            _ => None,
+        };
+        if let Some(cmp) = cmp {
+            self.ops.push(Op::Cmp(dst, lhs_reg, rhs_reg, cmp));
+            return Ok(dst);
+        }
+        match op {
+            BinOp::Add => self.ops.push(Op::Add(dst, lhs_reg, rhs_reg)),
+            BinOp::Sub => self.ops.push(Op::Sub(dst, lhs_reg, rhs_reg)),
+            BinOp::Mul => self.ops.push(Op::Mul(dst, lhs_reg, rhs_reg)),
+            other => return Err(TypeError(format!("IR lowering: unsupported operator {:?}", other))),
+        }
+        Ok(dst)
+    }
+
+    fn lower_stmt(&mut self, stmt: &ast::Stmt) -> TypeResult<()> {
+        match stmt {
+            ast::Stmt::Let { name, init, .. } => {
+                if let ast::Expr::ArrayLit(elems) = init {
+                    let (base, dims) = self.lower_array_literal(elems)?;
+                    self.bind(name, Binding::Array { base, dims });
+                } else {
+                    let mut reg = self.lower_expr(init)?;
+                    // A bare identifier lowers to the source variable's
+                    // own register; copy it so the new binding doesn't
+                    // alias (and later overwrite) that variable.
+                    if let ast::Expr::Ident(_) = init {
+                        let copy = self.alloc_reg();
+                        self.ops.push(Op::Move(copy, reg));
+                        reg = copy;
+                    }
+                    self.bind(name, Binding::Scalar(reg));
+                }
+            }
+            ast::Stmt::Assign { target, value } => match target {
+                ast::Expr::Ident(name) => {
+                    let value_reg = self.lower_expr(value)?;
+                    let var_reg = self.lookup_scalar(name)?;
+                    self.ops.push(Op::Move(var_reg, value_reg));
+                }
+                ast::Expr::Index(_, _) => {
+                    let (base, addr) = self.lower_array_address(target)?;
+                    let value_reg = self.lower_expr(value)?;
+                    self.ops.push(Op::Store(base, addr, value_reg));
+                }
+                other => return Err(TypeError(format!("IR lowering: unsupported assignment target {:?}", other))),
+            },
+            ast::Stmt::Expr(e) => {
+                self.lower_expr(e)?;
+            }
+            ast::Stmt::Return(value) => {
+                let reg = value.as_ref().map(|e| self.lower_expr(e)).transpose()?;
+                self.ops.push(Op::Return(reg));
+            }
+            ast::Stmt::If { cond, then_branch, else_branch } => {
+                let cond_reg = self.lower_expr(cond)?;
+                let branch_idx = self.ops.len();
+                self.ops.push(Op::BranchIfFalse(cond_reg, usize::MAX));
+                self.push_scope();
+                for s in then_branch {
+                    self.lower_stmt(s)?;
+                }
+                self.pop_scope();
+                match else_branch {
+                    Some(else_stmts) => {
+                        // Skip the trailing jump-past-else entirely when
+                        // the `then` branch's last statement is a
+                        // `return` - the jump would be unreachable.
+                        // (Checking the last emitted op instead would
+                        // also match a nested `if`'s inner `return`,
+                        // whose not-taken path must still skip `else`.)
+                        let then_returns = matches!(then_branch.last(), Some(ast::Stmt::Return(_)));
+                        let jump_idx = if then_returns {
+                            None
+                        } else {
+                            let idx = self.ops.len();
+                            self.ops.push(Op::Jump(usize::MAX));
+                            Some(idx)
+                        };
+                        let else_start = self.ops.len();
+                        patch_target(&mut self.ops[branch_idx], else_start);
+                        self.push_scope();
+                        for s in else_stmts {
+                            self.lower_stmt(s)?;
+                        }
+                        self.pop_scope();
+                        if let Some(jump_idx) = jump_idx {
+                            let end = self.ops.len();
+                            patch_target(&mut self.ops[jump_idx], end);
+                        }
+                    }
+                    None => {
+                        let end = self.ops.len();
+                        patch_target(&mut self.ops[branch_idx], end);
+                    }
+                }
+            }
+            ast::Stmt::While { cond, body } => {
+                let loop_start = self.ops.len();
+                let cond_reg = self.lower_expr(cond)?;
+                let branch_idx = self.ops.len();
+                self.ops.push(Op::BranchIfFalse(cond_reg, usize::MAX));
+                self.push_scope();
+                for s in body {
+                    self.lower_stmt(s)?;
+                }
+                self.pop_scope();
+                self.ops.push(Op::Jump(loop_start));
+                let end = self.ops.len();
+                patch_target(&mut self.ops[branch_idx], end);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn patch_target(op: &mut Op, target: usize) {
+    match op {
+        Op::Jump(t) | Op::BranchIfFalse(_, t) => *t = target,
+        other => panic!("patch_target called on non-jump op {:?}", other),
+    }
+}
+
+pub fn lower_function(f: &ast::Function, globals: &HashMap<String, IrValue>) -> TypeResult<IrFunction> {
+    let mut lowerer = Lowerer { globals, scopes: vec![HashMap::new()], consts: Vec::new(), ops: Vec::new(), next_reg: 0 };
+    for p in &f.params {
+        let reg = lowerer.alloc_reg();
+        lowerer.bind(&p.name, Binding::Scalar(reg));
+    }
+    for stmt in &f.body {
+        lowerer.lower_stmt(stmt)?;
+    }
+    // Always append a guaranteed landing pad, even if the body's last
+    // statement already returns: an `if`/`else` where only one arm
+    // returns leaves a live `Jump` targeting "whatever comes after the
+    // statement", and that target must resolve to a real op.
+    lowerer.ops.push(Op::Return(None));
+    Ok(IrFunction { name: f.name.clone(), num_params: f.params.len(), num_regs: lowerer.next_reg, consts: lowerer.consts, ops: lowerer.ops })
+}
+
+fn op_reads(op: &Op) -> Vec<Reg> {
+    match op {
+        Op::LoadConst(_, _) => vec![],
+        Op::Move(_, src) => vec![*src],
+        Op::AddConst(_, src, _) | Op::MulConst(_, src, _) => vec![*src],
+        Op::Add(_, a, b) | Op::Sub(_, a, b) | Op::Mul(_, a, b) => vec![*a, *b],
+        Op::Cmp(_, a, b, _) => vec![*a, *b],
+        Op::Jump(_) => vec![],
+        Op::BranchIfFalse(r, _) => vec![*r],
+        Op::Call(_, _, args) => args.clone(),
+        Op::Return(r) => r.iter().copied().collect(),
+        Op::Alloc(_, _) => vec![],
+        Op::Store(base, offset, src) => vec![*base, *offset, *src],
+        Op::Load(_, base, offset) => vec![*base, *offset],
+    }
+}
+
+fn op_dst(op: &Op) -> Option<Reg> {
+    match op {
+        Op::LoadConst(d, _)
+        | Op::Move(d, _)
+        | Op::AddConst(d, _, _)
+        | Op::MulConst(d, _, _)
+        | Op::Add(d, _, _)
+        | Op::Sub(d, _, _)
+        | Op::Mul(d, _, _)
+        | Op::Cmp(d, _, _, _)
+        | Op::Call(d, _, _)
+        | Op::Alloc(d, _)
+        | Op::Load(d, _, _) => Some(*d),
+        Op::Jump(_) | Op::BranchIfFalse(_, _) | Op::Return(_) | Op::Store(_, _, _) => None,
+    }
+}
+
+/// The ops an index can transfer control to: empty for `Return` (a
+/// terminal op), both branch arms for `BranchIfFalse`, the jump target
+/// alone for `Jump`, and the next op otherwise.
+fn op_successors(idx: usize, op: &Op) -> Vec<usize> {
+    match op {
+        Op::Jump(t) => vec![*t],
+        Op::BranchIfFalse(_, t) => vec![idx + 1, *t],
+        Op::Return(_) => vec![],
+        _ => vec![idx + 1],
+    }
+}
+
+/// Checks two structural invariants before a function is trusted to run.
+///
+/// First, that control flow never runs off the end of the op list:
+/// every successor index is in range, and every reachable op with no
+/// successor is a `Return`, computed from the actual CFG (reachable ops
+/// and their successors). For a function built by `lower_function`, a
+/// path that falls off the end of the body reaches the appended
+/// `Return(None)` landing pad and passes this check, so a `-> T`
+/// function that lacks a `return` on some path is not rejected here.
+///
+/// Second, that every register read is defined on *every* path that
+/// reaches it, via a forward must-be-assigned dataflow (join = set
+/// intersection) rather than "is this register written anywhere in the
+/// function" - the latter is flow-insensitive and would pass a register
+/// that's only assigned on a branch the read doesn't run on.
+pub fn verify(f: &IrFunction) -> TypeResult<()> {
+    let n = f.ops.len();
+    let successors: Vec<Vec<usize>> = f.ops.iter().enumerate().map(|(idx, op)| op_successors(idx, op)).collect();
+    for (idx, succs) in successors.iter().enumerate() {
+        for &s in succs {
+            if s >= n {
+                return Err(TypeError(format!("ir verifier: `{}` jumps out of range to {} at op {}", f.name, s, idx)));
+            }
+        }
+    }
+
+    let mut reachable = vec![false; n];
+    let mut stack = vec![0usize];
+    while let Some(idx) = stack.pop() {
+        if idx >= n || reachable[idx] {
+            continue;
+        }
+        reachable[idx] = true;
+        stack.extend(successors[idx].iter().copied());
+    }
+    for (idx, op) in f.ops.iter().enumerate() {
+        if reachable[idx] && successors[idx].is_empty() && !matches!(op, Op::Return(_)) {
+            return Err(TypeError(format!("ir verifier: `{}` does not reach a Return on every path (falls off the end at op {})", f.name, idx)));
+        }
+    }
+
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (idx, succs) in successors.iter().enumerate() {
+        for &s in succs {
+            predecessors[s].push(idx);
+        }
+    }
+
+    let num_regs = f.num_regs as usize;
+    let mut entry_assigned = vec![false; num_regs];
+    for slot in entry_assigned.iter_mut().take(f.num_params) {
+        *slot = true;
+    }
+    let mut assigned_before: Vec<Vec<bool>> = vec![vec![true; num_regs]; n];
+    assigned_before[0] = entry_assigned.clone();
+
+    let assigned_after = |idx: usize, before: &[bool]| -> Vec<bool> {
+        let mut after = before.to_vec();
+        if let Some(dst) = op_dst(&f.ops[idx]) {
+            after[dst as usize] = true;
+        }
+        after
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for idx in 0..n {
+            if !reachable[idx] {
+                continue;
+            }
+            let new_before = if idx == 0 {
+                entry_assigned.clone()
+            } else {
+                let mut meet: Option<Vec<bool>> = None;
+                for &pred in &predecessors[idx] {
+                    if !reachable[pred] {
+                        continue;
+                    }
+                    let out = assigned_after(pred, &assigned_before[pred]);
+                    meet = Some(match meet {
+                        None => out,
+                        Some(acc) => acc.iter().zip(out.iter()).map(|(a, b)| *a && *b).collect(),
+                    });
+                }
+                meet.unwrap_or_else(|| vec![true; num_regs])
+            };
+            if new_before != assigned_before[idx] {
+                assigned_before[idx] = new_before;
+                changed = true;
+            }
+        }
+    }
+
+    for (idx, op) in f.ops.iter().enumerate() {
+        if !reachable[idx] {
+            continue;
+        }
+        for reg in op_reads(op) {
+            if !assigned_before[idx].get(reg as usize).copied().unwrap_or(false) {
+                return Err(TypeError(format!(
+                    "ir verifier: `{}` reads register r{} at op {} on a path where it isn't definitely assigned",
+                    f.name, reg, idx
+                )));
+            }
+        }
+        if let Some(dst) = op_dst(op) {
+            if dst as usize >= num_regs {
+                return Err(TypeError(format!("ir verifier: `{}` writes out-of-range register r{}", f.name, dst)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub struct IrInterpreter {
+    functions: HashMap<String, Rc<IrFunction>>,
+}
+
+impl IrInterpreter {
+    pub fn new(functions: Vec<IrFunction>) -> Self {
+        let functions = functions.into_iter().map(|f| (f.name.clone(), Rc::new(f))).collect();
+        IrInterpreter { functions }
+    }
+
+    pub fn run(&mut self, name: &str, args: Vec<IrValue>) -> Option<IrValue> {
+        let f = self.functions.get(name).expect("undefined IR function").clone();
+        let mut regs = vec![IrValue::Int(0); f.num_regs as usize];
+        for (i, arg) in args.into_iter().enumerate() {
+            regs[i] = arg;
+        }
+        // Backs every `Alloc`/`Load`/`Store` in this call: one flat
+        // arena per invocation, the same lifetime `regs` has.
+        let mut mem: Vec<IrValue> = Vec::new();
+
+        let mut pc = 0usize;
+        loop {
+            match &f.ops[pc] {
+                Op::LoadConst(dst, idx) => {
+                    regs[*dst as usize] = f.consts[*idx];
+                    pc += 1;
+                }
+                Op::Move(dst, src) => {
+                    regs[*dst as usize] = regs[*src as usize];
+                    pc += 1;
+                }
+                Op::AddConst(dst, src, imm) => {
+                    regs[*dst as usize] = arith_const(regs[*src as usize], *imm, i64::wrapping_add, |a, b| a + b as f64);
+                    pc += 1;
+                }
+                Op::MulConst(dst, src, imm) => {
+                    regs[*dst as usize] = arith_const(regs[*src as usize], *imm, i64::wrapping_mul, |a, b| a * b as f64);
+                    pc += 1;
+                }
+                Op::Add(dst, a, b) => {
+                    regs[*dst as usize] = arith(regs[*a as usize], regs[*b as usize], i64::wrapping_add, |a, b| a + b);
+                    pc += 1;
+                }
+                Op::Sub(dst, a, b) => {
+                    regs[*dst as usize] = arith(regs[*a as usize], regs[*b as usize], i64::wrapping_sub, |a, b| a - b);
+                    pc += 1;
+                }
+                Op::Mul(dst, a, b) => {
+                    regs[*dst as usize] = arith(regs[*a as usize], regs[*b as usize], i64::wrapping_mul, |a, b| a * b);
+                    pc += 1;
+                }
+                Op::Cmp(dst, a, b, cmp) => {
+                    let ordering = match (regs[*a as usize], regs[*b as usize]) {
+                        (IrValue::Float(a), IrValue::Float(b)) => a.partial_cmp(&b).expect("NaN comparison"),
+                        (a, b) => a.as_i64().cmp(&b.as_i64()),
+                    };
+                    let result = match cmp {
+                        CmpOp::Lt => ordering.is_lt(),
+                        CmpOp::Gt => ordering.is_gt(),
+                        CmpOp::Le => ordering.is_le(),
+                        CmpOp::Ge => ordering.is_ge(),
+                        CmpOp::Eq => ordering.is_eq(),
+                        CmpOp::Ne => !ordering.is_eq(),
+                    };
+                    regs[*dst as usize] = IrValue::Int(result as i64);
+                    pc += 1;
+                }
+                Op::Jump(target) => pc = *target,
+                Op::BranchIfFalse(cond, target) => {
+                    if regs[*cond as usize].as_i64() == 0 {
+                        pc = *target;
+                    } else {
+                        pc += 1;
+                    }
+                }
+                Op::Call(dst, name, args) => {
+                    let arg_values: Vec<IrValue> = args.iter().map(|r| regs[*r as usize]).collect();
+                    regs[*dst as usize] = self.run(name, arg_values).unwrap_or(IrValue::Int(0));
+                    pc += 1;
+                }
+                Op::Return(reg) => return reg.map(|r| regs[r as usize]),
+                Op::Alloc(dst, size) => {
+                    let base = mem.len();
+                    mem.resize(mem.len() + size, IrValue::Int(0));
+                    regs[*dst as usize] = IrValue::Int(base as i64);
+                    pc += 1;
+                }
+                Op::Store(base, offset, src) => {
+                    let addr = (regs[*base as usize].as_i64() + regs[*offset as usize].as_i64()) as usize;
+                    mem[addr] = regs[*src as usize];
+                    pc += 1;
+                }
+                Op::Load(dst, base, offset) => {
+                    let addr = (regs[*base as usize].as_i64() + regs[*offset as usize].as_i64()) as usize;
+                    regs[*dst as usize] = mem[addr];
+                    pc += 1;
+                }
+            }
+        }
+    }
+}
+
+fn arith(a: IrValue, b: IrValue, int_op: fn(i64, i64) -> i64, float_op: fn(f64, f64) -> f64) -> IrValue {
+    match (a, b) {
+        (IrValue::Int(a), IrValue::Int(b)) => IrValue::Int(int_op(a, b)),
+        (IrValue::Float(a), IrValue::Float(b)) => IrValue::Float(float_op(a, b)),
+        (IrValue::Float(a), IrValue::Int(b)) => IrValue::Float(float_op(a, b as f64)),
+        (IrValue::Int(a), IrValue::Float(b)) => IrValue::Float(float_op(a as f64, b)),
+    }
+}
+
+fn arith_const(a: IrValue, imm: i64, int_op: fn(i64, i64) -> i64, float_op: fn(f64, i64) -> f64) -> IrValue {
+    match a {
+        IrValue::Int(a) => IrValue::Int(int_op(a, imm)),
+        IrValue::Float(a) => IrValue::Float(float_op(a, imm)),
+    }
+}
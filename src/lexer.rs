@@ -0,0 +1,246 @@
+//! Tokenizer: turns raw source text into a flat stream of `Token`s for the
+//! parser to consume. No layout sensitivity, C-style `//` comments only.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Ident(String),
+    IntLit(i64),
+    FloatLit(f64),
+
+    // Keywords
+    Fn,
+    Const,
+    Enum,
+    Return,
+    If,
+    Else,
+    While,
+    True,
+    False,
+    As,
+
+    // Punctuation
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Semi,
+    Colon,
+    ColonColon,
+    Arrow,
+
+    // Operators
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Amp,
+    Pipe,
+    Caret,
+    Tilde,
+    Shl,
+    Shr,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    EqEq,
+    Ne,
+    Eq,
+
+    Eof,
+}
+
+pub struct Lexer<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Lexer { src: src.as_bytes(), pos: 0 }
+    }
+
+    pub fn tokenize(mut self) -> Vec<Token> {
+        let mut out = Vec::new();
+        loop {
+            let tok = self.next_token();
+            let done = tok == Token::Eof;
+            out.push(tok);
+            if done {
+                break;
+            }
+        }
+        out
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn peek2(&self) -> Option<u8> {
+        self.src.get(self.pos + 1).copied()
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') => self.pos += 1,
+                Some(b'/') if self.peek2() == Some(b'/') => {
+                    while self.peek().is_some() && self.peek() != Some(b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Token {
+        self.skip_trivia();
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Token::Eof,
+        };
+
+        if c.is_ascii_digit() {
+            return self.lex_number();
+        }
+        if c.is_ascii_alphabetic() || c == b'_' {
+            return self.lex_ident();
+        }
+
+        self.pos += 1;
+        match c {
+            b'(' => Token::LParen,
+            b')' => Token::RParen,
+            b'{' => Token::LBrace,
+            b'}' => Token::RBrace,
+            b'[' => Token::LBracket,
+            b']' => Token::RBracket,
+            b',' => Token::Comma,
+            b';' => Token::Semi,
+            b':' => {
+                if self.peek() == Some(b':') {
+                    self.pos += 1;
+                    Token::ColonColon
+                } else {
+                    Token::Colon
+                }
+            }
+            b'+' => Token::Plus,
+            b'-' => {
+                if self.peek() == Some(b'>') {
+                    self.pos += 1;
+                    Token::Arrow
+                } else {
+                    Token::Minus
+                }
+            }
+            b'*' => Token::Star,
+            b'/' => Token::Slash,
+            b'%' => Token::Percent,
+            b'~' => Token::Tilde,
+            b'^' => Token::Caret,
+            b'&' => Token::Amp,
+            b'|' => Token::Pipe,
+            b'<' => {
+                if self.peek() == Some(b'<') {
+                    self.pos += 1;
+                    Token::Shl
+                } else if self.peek() == Some(b'=') {
+                    self.pos += 1;
+                    Token::Le
+                } else {
+                    Token::Lt
+                }
+            }
+            b'>' => {
+                if self.peek() == Some(b'>') {
+                    self.pos += 1;
+                    Token::Shr
+                } else if self.peek() == Some(b'=') {
+                    self.pos += 1;
+                    Token::Ge
+                } else {
+                    Token::Gt
+                }
+            }
+            b'=' => {
+                if self.peek() == Some(b'=') {
+                    self.pos += 1;
+                    Token::EqEq
+                } else {
+                    Token::Eq
+                }
+            }
+            b'!' if self.peek() == Some(b'=') => {
+                self.pos += 1;
+                Token::Ne
+            }
+            other => panic!("lexer: unexpected character '{}' at byte {}", other as char, self.pos),
+        }
+    }
+
+    fn lex_number(&mut self) -> Token {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let mut is_float = false;
+        if self.peek() == Some(b'.') && self.peek2().is_some_and(|c| c.is_ascii_digit()) {
+            is_float = true;
+            self.pos += 1;
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.pos += 1;
+            }
+        }
+        // Optional exponent (`1e10`, `1.5e-3`): forces the literal to be
+        // a float even without a decimal point, same as Rust's own
+        // float-literal grammar.
+        if matches!(self.peek(), Some(b'e') | Some(b'E')) {
+            let mut look = self.pos + 1;
+            if matches!(self.src.get(look).copied(), Some(b'+') | Some(b'-')) {
+                look += 1;
+            }
+            if self.src.get(look).is_some_and(|c| c.is_ascii_digit()) {
+                is_float = true;
+                self.pos = look;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+        }
+        let text = std::str::from_utf8(&self.src[start..self.pos]).unwrap();
+        if is_float {
+            Token::FloatLit(text.parse().unwrap())
+        } else {
+            Token::IntLit(text.parse().unwrap())
+        }
+    }
+
+    fn lex_ident(&mut self) -> Token {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_') {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.src[start..self.pos]).unwrap();
+        match text {
+            "fn" => Token::Fn,
+            "const" => Token::Const,
+            "enum" => Token::Enum,
+            "return" => Token::Return,
+            "if" => Token::If,
+            "else" => Token::Else,
+            "while" => Token::While,
+            "true" => Token::True,
+            "false" => Token::False,
+            "as" => Token::As,
+            _ => Token::Ident(text.to_string()),
+        }
+    }
+}
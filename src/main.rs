@@ -0,0 +1,65 @@
+mod ast;
+mod error;
+mod eval;
+mod intrinsics;
+mod ir;
+mod lexer;
+mod parser;
+mod repl;
+mod typeck;
+mod types;
+
+use std::env;
+use std::fs;
+use std::process;
+
+use eval::Interpreter;
+use lexer::Lexer;
+use parser::Parser;
+use typeck::TypeChecker;
+
+fn main() {
+    if env::args().any(|a| a == "--repl") {
+        repl::run();
+        return;
+    }
+    let check_ir = env::args().any(|a| a == "--check-ir");
+
+    let path = env::args().nth(1).unwrap_or_else(|| "input/test.rs".to_string());
+    let src = fs::read_to_string(&path).unwrap_or_else(|e| {
+        eprintln!("error: could not read `{}`: {}", path, e);
+        process::exit(1);
+    });
+
+    let tokens = Lexer::new(&src).tokenize();
+    let program = Parser::new(tokens).parse_program();
+
+    if let Err(e) = TypeChecker::new().check_program(&program) {
+        eprintln!("type error: {}", e);
+        process::exit(1);
+    }
+
+    let mut interp = Interpreter::new(&program);
+    interp.call("main", Vec::new());
+
+    if check_ir {
+        // Opt-in smoke check for the register-IR backend: it only
+        // covers a subset of the language (see `ir` module docs), so
+        // most programs have nothing in them for it to run, and
+        // "does `fib` lower and compute the right answer" only means
+        // anything for a program that actually defines one.
+        let ir_fns = ir::lower_program(&program);
+        for ir_fn in &ir_fns {
+            if let Err(e) = ir::verify(ir_fn) {
+                eprintln!("ir verifier: {}", e);
+                process::exit(1);
+            }
+        }
+        let has_fib = ir_fns.iter().any(|f| f.name == "fib");
+        let mut ir_interp = ir::IrInterpreter::new(ir_fns);
+        if has_fib {
+            let result = ir_interp.run("fib", vec![ir::IrValue::Int(10)]);
+            println!("ir: fib(10) = {:?}", result);
+        }
+    }
+}
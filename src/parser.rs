@@ -0,0 +1,512 @@
+//! Recursive-descent parser: `Vec<Token>` -> `Program`.
+//!
+//! Operator precedence (loosest to tightest), matching the grammar this
+//! language has grown: `| -> ^ -> & -> == != -> < > <= >= -> << >> ->
+//! + - -> * / % -> as` -> unary -> postfix -> primary.
+
+use crate::ast::*;
+use crate::lexer::Token;
+use crate::types::Type;
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Parser { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        self.tokens.get(self.pos + offset).unwrap_or(&Token::Eof)
+    }
+
+    fn bump(&mut self) -> Token {
+        let t = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        t
+    }
+
+    fn expect(&mut self, expected: Token) -> Token {
+        let got = self.bump();
+        if got != expected {
+            panic!("parser: expected {:?}, found {:?}", expected, got);
+        }
+        got
+    }
+
+    fn expect_ident(&mut self) -> String {
+        match self.bump() {
+            Token::Ident(name) => name,
+            other => panic!("parser: expected identifier, found {:?}", other),
+        }
+    }
+
+    pub fn parse_program(&mut self) -> Program {
+        let mut items = Vec::new();
+        while *self.peek() != Token::Eof {
+            items.push(self.parse_item());
+        }
+        Program { items }
+    }
+
+    fn parse_item(&mut self) -> Item {
+        match self.peek() {
+            Token::Const => Item::Const(self.parse_const()),
+            Token::Fn => Item::Function(self.parse_function()),
+            Token::Enum => Item::Enum(self.parse_enum()),
+            other => panic!("parser: expected item, found {:?}", other),
+        }
+    }
+
+    /// Parses one REPL entry: a `const`/`fn`/`enum` declaration, or
+    /// otherwise a single statement - the same grammar `parse_block`
+    /// accepts, just without the surrounding `{ }`.
+    pub fn parse_repl_entry(&mut self) -> ReplEntry {
+        match self.peek() {
+            Token::Const | Token::Fn | Token::Enum => ReplEntry::Item(self.parse_item()),
+            _ => ReplEntry::Stmt(self.parse_stmt()),
+        }
+    }
+
+    fn parse_const(&mut self) -> Const {
+        self.expect(Token::Const);
+        let name = self.expect_ident();
+        self.expect(Token::Colon);
+        let ty = self.parse_type();
+        self.expect(Token::Eq);
+        let value = self.parse_expr();
+        self.expect(Token::Semi);
+        Const { name, ty, value }
+    }
+
+    /// `enum Name { A, B, C }` or `enum Name: T { A, B, C }` - the
+    /// underlying integer type defaults to `i32` when omitted, mirroring
+    /// `parse_function`'s `-> T` defaulting to `void`.
+    fn parse_enum(&mut self) -> EnumDecl {
+        self.expect(Token::Enum);
+        let name = self.expect_ident();
+        let underlying = if *self.peek() == Token::Colon {
+            self.bump();
+            self.parse_type()
+        } else {
+            Type::I32
+        };
+        self.expect(Token::LBrace);
+        let mut variants = Vec::new();
+        if *self.peek() != Token::RBrace {
+            loop {
+                variants.push(self.expect_ident());
+                if *self.peek() == Token::Comma {
+                    self.bump();
+                } else {
+                    break;
+                }
+            }
+        }
+        self.expect(Token::RBrace);
+        EnumDecl { name, underlying, variants }
+    }
+
+    fn parse_function(&mut self) -> Function {
+        self.expect(Token::Fn);
+        let name = self.expect_ident();
+        self.expect(Token::LParen);
+        let mut params = Vec::new();
+        if *self.peek() != Token::RParen {
+            loop {
+                let pname = self.expect_ident();
+                self.expect(Token::Colon);
+                let ty = self.parse_type();
+                params.push(Param { name: pname, ty });
+                if *self.peek() == Token::Comma {
+                    self.bump();
+                } else {
+                    break;
+                }
+            }
+        }
+        self.expect(Token::RParen);
+        let ret = if *self.peek() == Token::Arrow {
+            self.bump();
+            self.parse_type()
+        } else {
+            Type::Void
+        };
+        let body = self.parse_block();
+        Function { name, params, ret, body }
+    }
+
+    fn parse_block(&mut self) -> Vec<Stmt> {
+        self.expect(Token::LBrace);
+        let mut stmts = Vec::new();
+        while *self.peek() != Token::RBrace {
+            stmts.push(self.parse_stmt());
+        }
+        self.expect(Token::RBrace);
+        stmts
+    }
+
+    fn parse_stmt(&mut self) -> Stmt {
+        match self.peek() {
+            Token::If => self.parse_if(),
+            Token::While => self.parse_while(),
+            Token::Return => {
+                self.bump();
+                let value = if *self.peek() == Token::Semi { None } else { Some(self.parse_expr()) };
+                self.expect(Token::Semi);
+                Stmt::Return(value)
+            }
+            Token::Ident(_) if *self.peek_at(1) == Token::Colon => self.parse_let(),
+            _ => {
+                let expr = self.parse_expr();
+                if *self.peek() == Token::Eq {
+                    self.bump();
+                    let value = self.parse_expr();
+                    self.expect(Token::Semi);
+                    Stmt::Assign { target: expr, value }
+                } else {
+                    self.expect(Token::Semi);
+                    Stmt::Expr(expr)
+                }
+            }
+        }
+    }
+
+    fn parse_let(&mut self) -> Stmt {
+        let name = self.expect_ident();
+        self.expect(Token::Colon);
+        let ty = self.parse_type();
+        self.expect(Token::Eq);
+        let init = self.parse_expr();
+        self.expect(Token::Semi);
+        Stmt::Let { name, ty, init }
+    }
+
+    fn parse_if(&mut self) -> Stmt {
+        self.expect(Token::If);
+        self.expect(Token::LParen);
+        let cond = self.parse_expr();
+        self.expect(Token::RParen);
+        let then_branch = self.parse_block();
+        let else_branch = if *self.peek() == Token::Else {
+            self.bump();
+            Some(if *self.peek() == Token::If {
+                vec![self.parse_if()]
+            } else {
+                self.parse_block()
+            })
+        } else {
+            None
+        };
+        Stmt::If { cond, then_branch, else_branch }
+    }
+
+    fn parse_while(&mut self) -> Stmt {
+        self.expect(Token::While);
+        self.expect(Token::LParen);
+        let cond = self.parse_expr();
+        self.expect(Token::RParen);
+        let body = self.parse_block();
+        Stmt::While { cond, body }
+    }
+
+    // ---- Types ----------------------------------------------------------
+
+    fn parse_type(&mut self) -> Type {
+        let mut ty = self.parse_type_atom();
+        loop {
+            match self.peek() {
+                Token::Star => {
+                    self.bump();
+                    ty = Type::Pointer(Box::new(ty));
+                }
+                Token::LBracket => {
+                    self.bump();
+                    let size = if let Token::IntLit(n) = self.peek() {
+                        let n = *n as usize;
+                        self.bump();
+                        Some(n)
+                    } else {
+                        None
+                    };
+                    self.expect(Token::RBracket);
+                    ty = Type::Array(Box::new(ty), size);
+                }
+                _ => break,
+            }
+        }
+        ty
+    }
+
+    fn parse_type_atom(&mut self) -> Type {
+        match self.bump() {
+            Token::Ident(name) => match name.as_str() {
+                "i32" => Type::I32,
+                "i64" => Type::I64,
+                "u32" => Type::U32,
+                "u64" => Type::U64,
+                "f32" => Type::F32,
+                "f64" => Type::F64,
+                "bool" => Type::Bool,
+                "void" => Type::Void,
+                // Anything else names a user-defined enum; the
+                // type-checker's enum registry is what actually
+                // validates it exists.
+                other => Type::Enum(other.to_string()),
+            },
+            Token::Fn => {
+                self.expect(Token::LParen);
+                let mut params = Vec::new();
+                if *self.peek() != Token::RParen {
+                    loop {
+                        params.push(self.parse_type());
+                        if *self.peek() == Token::Comma {
+                            self.bump();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RParen);
+                self.expect(Token::Arrow);
+                let ret = self.parse_type();
+                Type::Function(params, Box::new(ret))
+            }
+            Token::LParen => {
+                let ty = self.parse_type();
+                self.expect(Token::RParen);
+                ty
+            }
+            other => panic!("parser: expected type, found {:?}", other),
+        }
+    }
+
+    // ---- Expressions ------------------------------------------------------
+    // Precedence climbing, loosest to tightest.
+
+    fn parse_expr(&mut self) -> Expr {
+        self.parse_bitor()
+    }
+
+    fn parse_bitor(&mut self) -> Expr {
+        let mut lhs = self.parse_bitxor();
+        while *self.peek() == Token::Pipe {
+            self.bump();
+            let rhs = self.parse_bitxor();
+            lhs = Expr::Binary(BinOp::BitOr, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_bitxor(&mut self) -> Expr {
+        let mut lhs = self.parse_bitand();
+        while *self.peek() == Token::Caret {
+            self.bump();
+            let rhs = self.parse_bitand();
+            lhs = Expr::Binary(BinOp::BitXor, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_bitand(&mut self) -> Expr {
+        let mut lhs = self.parse_equality();
+        while *self.peek() == Token::Amp {
+            self.bump();
+            let rhs = self.parse_equality();
+            lhs = Expr::Binary(BinOp::BitAnd, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_equality(&mut self) -> Expr {
+        let mut lhs = self.parse_comparison();
+        loop {
+            let op = match self.peek() {
+                Token::EqEq => BinOp::Eq,
+                Token::Ne => BinOp::Ne,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_comparison();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_comparison(&mut self) -> Expr {
+        let mut lhs = self.parse_shift();
+        loop {
+            let op = match self.peek() {
+                Token::Lt => BinOp::Lt,
+                Token::Gt => BinOp::Gt,
+                Token::Le => BinOp::Le,
+                Token::Ge => BinOp::Ge,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_shift();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_shift(&mut self) -> Expr {
+        let mut lhs = self.parse_additive();
+        loop {
+            let op = match self.peek() {
+                Token::Shl => BinOp::Shl,
+                Token::Shr => BinOp::Shr,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_additive();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_additive(&mut self) -> Expr {
+        let mut lhs = self.parse_term();
+        loop {
+            let op = match self.peek() {
+                Token::Plus => BinOp::Add,
+                Token::Minus => BinOp::Sub,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_term();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    fn parse_term(&mut self) -> Expr {
+        let mut lhs = self.parse_cast();
+        loop {
+            let op = match self.peek() {
+                Token::Star => BinOp::Mul,
+                Token::Slash => BinOp::Div,
+                Token::Percent => BinOp::Rem,
+                _ => break,
+            };
+            self.bump();
+            let rhs = self.parse_cast();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        lhs
+    }
+
+    /// `expr as T`, left-associative and chainable (`x as i64 as f64`).
+    /// Binds tighter than any binary operator but looser than unary/
+    /// postfix, matching how `as` reads in `expr as T`.
+    fn parse_cast(&mut self) -> Expr {
+        let mut expr = self.parse_unary();
+        while *self.peek() == Token::As {
+            self.bump();
+            let ty = self.parse_type();
+            expr = Expr::Cast(Box::new(expr), ty);
+        }
+        expr
+    }
+
+    fn parse_unary(&mut self) -> Expr {
+        match self.peek() {
+            Token::Minus => {
+                self.bump();
+                Expr::Unary(UnaryOp::Neg, Box::new(self.parse_unary()))
+            }
+            Token::Amp => {
+                self.bump();
+                Expr::Unary(UnaryOp::Ref, Box::new(self.parse_unary()))
+            }
+            Token::Star => {
+                self.bump();
+                Expr::Unary(UnaryOp::Deref, Box::new(self.parse_unary()))
+            }
+            Token::Tilde => {
+                self.bump();
+                Expr::Unary(UnaryOp::BitNot, Box::new(self.parse_unary()))
+            }
+            _ => self.parse_postfix(),
+        }
+    }
+
+    fn parse_postfix(&mut self) -> Expr {
+        let mut expr = self.parse_primary();
+        loop {
+            match self.peek() {
+                Token::LParen => {
+                    self.bump();
+                    let mut args = Vec::new();
+                    if *self.peek() != Token::RParen {
+                        loop {
+                            args.push(self.parse_expr());
+                            if *self.peek() == Token::Comma {
+                                self.bump();
+                            } else {
+                                break;
+                            }
+                        }
+                    }
+                    self.expect(Token::RParen);
+                    expr = Expr::Call(Box::new(expr), args);
+                }
+                Token::LBracket => {
+                    self.bump();
+                    let index = self.parse_expr();
+                    self.expect(Token::RBracket);
+                    expr = Expr::Index(Box::new(expr), Box::new(index));
+                }
+                _ => break,
+            }
+        }
+        expr
+    }
+
+    fn parse_primary(&mut self) -> Expr {
+        match self.bump() {
+            Token::IntLit(n) => Expr::IntLit(n),
+            Token::FloatLit(n) => Expr::FloatLit(n),
+            Token::True => Expr::BoolLit(true),
+            Token::False => Expr::BoolLit(false),
+            Token::Ident(name) => {
+                if *self.peek() == Token::ColonColon {
+                    self.bump();
+                    let variant = self.expect_ident();
+                    Expr::EnumVariant(name, variant)
+                } else {
+                    Expr::Ident(name)
+                }
+            }
+            Token::LParen => {
+                let expr = self.parse_expr();
+                self.expect(Token::RParen);
+                expr
+            }
+            Token::LBrace => {
+                let mut elems = Vec::new();
+                if *self.peek() != Token::RBrace {
+                    loop {
+                        elems.push(self.parse_expr());
+                        if *self.peek() == Token::Comma {
+                            self.bump();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                self.expect(Token::RBrace);
+                Expr::ArrayLit(elems)
+            }
+            other => panic!("parser: expected expression, found {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Interactive REPL: reads one entry at a time from stdin, type-checks
+//! and evaluates it against a persistent session (so `const`s and `fn`s
+//! from earlier entries, and `let`-bound locals, stay visible to later
+//! ones), and prints the result of a bare expression statement labeled
+//! with its static type.
+//!
+//! Each entry must fit on a single line - the lexer/parser read a whole
+//! token stream eagerly and don't buffer a partial one across lines, so
+//! a multi-statement `fn` body has to be written all on one line.
+
+use std::io::{self, Write};
+
+use crate::ast::{ReplEntry, Stmt};
+use crate::eval::{Interpreter, Value};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::typeck::TypeChecker;
+use crate::types::Type;
+
+pub fn run() {
+    let mut checker = TypeChecker::new_repl();
+    let mut interp = Interpreter::new_repl();
+    let stdin = io::stdin();
+
+    loop {
+        print!("> ");
+        io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let tokens = Lexer::new(line).tokenize();
+        let entry = Parser::new(tokens).parse_repl_entry();
+
+        match entry {
+            ReplEntry::Item(item) => match checker.declare_item(&item) {
+                Ok(()) => interp.declare_item(&item),
+                Err(e) => eprintln!("type error: {}", e),
+            },
+            ReplEntry::Stmt(stmt) => run_stmt(&mut checker, &mut interp, &stmt),
+        }
+    }
+}
+
+fn run_stmt(checker: &mut TypeChecker, interp: &mut Interpreter, stmt: &Stmt) {
+    let result_ty = match checker.check_repl_stmt(stmt) {
+        Ok(ty) => ty,
+        Err(e) => {
+            eprintln!("type error: {}", e);
+            return;
+        }
+    };
+    let value = interp.eval_repl_stmt(stmt);
+    if let (Some(value), Some(ty)) = (value, result_ty) {
+        println!("=> {}: {}", format_value(&value, &ty), ty);
+    }
+}
+
+/// Renders a value using its static type rather than the value alone:
+/// a pointer unwraps to `&<value>`, following through multiple levels
+/// (`i32**`); an array expands to a nested `{...}` literal element by
+/// element; a function pointer prints as the name it resolved to. The
+/// static type is the source of truth for *how* to recurse (e.g. an
+/// empty array's element type can't be read back off the value), even
+/// though `Value` already happens to tag most scalars with it too.
+fn format_value(value: &Value, ty: &Type) -> String {
+    match (value, ty) {
+        (Value::Ptr(cell), Type::Pointer(pointee_ty)) => {
+            format!("&{}", format_value(&cell.borrow(), pointee_ty))
+        }
+        (Value::Array(cells), Type::Array(elem_ty, _)) => {
+            let elems: Vec<String> = cells.iter().map(|c| format_value(&c.borrow(), elem_ty)).collect();
+            format!("{{{}}}", elems.join(", "))
+        }
+        (Value::FnPtr(name), _) => name.clone(),
+        (Value::I32(n), _) => n.to_string(),
+        (Value::I64(n), _) => n.to_string(),
+        (Value::U32(n), _) => n.to_string(),
+        (Value::U64(n), _) => n.to_string(),
+        (Value::F32(n), _) => n.to_string(),
+        (Value::F64(n), _) => n.to_string(),
+        (Value::Bool(b), _) => b.to_string(),
+        (Value::Enum(_, discriminant), _) => discriminant.to_string(),
+        (Value::Void, _) => "void".to_string(),
+        (other, ty) => panic!("cannot format value {:?} as {}", other, ty),
+    }
+}
@@ -0,0 +1,421 @@
+//! Static type-checker. Walks the AST once, resolving identifiers against
+//! a scope stack of locals backed by global consts and function
+//! signatures, and rejects anything the numeric-promotion lattice in
+//! `types::Type::promote` can't reconcile.
+
+use std::collections::HashMap;
+
+use crate::ast::*;
+use crate::error::{TypeError, TypeResult};
+use crate::intrinsics::{self, Intrinsic};
+use crate::types::Type;
+
+pub struct TypeChecker {
+    globals: HashMap<String, Type>,
+    enums: HashMap<String, EnumDecl>,
+    scopes: Vec<HashMap<String, Type>>,
+    current_return: Type,
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker { globals: HashMap::new(), enums: HashMap::new(), scopes: Vec::new(), current_return: Type::Void }
+    }
+
+    /// Constructs a checker for REPL use. `scopes` is seeded with one
+    /// scope that's never popped by top-level REPL entries, so a
+    /// `let`-like `x: i32 = 5;` typed in one entry is still visible when
+    /// the next entry is checked - functionally a persistent global
+    /// scope, same as `check_program`'s per-function scope never leaks
+    /// into the next function.
+    pub fn new_repl() -> Self {
+        TypeChecker { globals: HashMap::new(), enums: HashMap::new(), scopes: vec![HashMap::new()], current_return: Type::Void }
+    }
+
+    /// Declares a `const`, `fn`, or `enum` entered at the REPL, mirroring
+    /// the kinds of top-level item `check_program` handles for a whole file.
+    /// A function's signature is registered before its body is checked,
+    /// same as `check_program`'s two-pass setup, so it can call itself.
+    pub fn declare_item(&mut self, item: &Item) -> TypeResult<()> {
+        match item {
+            Item::Const(c) => {
+                self.globals.insert(c.name.clone(), c.ty.clone());
+                let actual = self.type_of_expr(&c.value)?;
+                self.expect_assignable(&actual, &c.ty, &format!("const `{}`", c.name))
+            }
+            Item::Function(f) => {
+                let params = f.params.iter().map(|p| p.ty.clone()).collect();
+                self.globals.insert(f.name.clone(), Type::Function(params, Box::new(f.ret.clone())));
+                // `check_function` manages its own scope stack from
+                // scratch, so the REPL's persistent session scope is set
+                // aside for the duration and restored afterward.
+                let session_scope = std::mem::take(&mut self.scopes);
+                let result = self.check_function(f);
+                self.scopes = session_scope;
+                result
+            }
+            Item::Enum(e) => {
+                self.enums.insert(e.name.clone(), e.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Type-checks one REPL statement against the persistent session
+    /// scope, returning the type of a bare expression statement (e.g.
+    /// `1 + 2;`) so the REPL can label its printed result.
+    pub fn check_repl_stmt(&mut self, stmt: &Stmt) -> TypeResult<Option<Type>> {
+        if let Stmt::Expr(e) = stmt {
+            return self.type_of_expr(e).map(Some);
+        }
+        self.check_stmt(stmt)?;
+        Ok(None)
+    }
+
+    pub fn check_program(&mut self, program: &Program) -> TypeResult<()> {
+        for item in &program.items {
+            match item {
+                Item::Const(c) => {
+                    self.globals.insert(c.name.clone(), c.ty.clone());
+                }
+                Item::Function(f) => {
+                    let params = f.params.iter().map(|p| p.ty.clone()).collect();
+                    self.globals.insert(f.name.clone(), Type::Function(params, Box::new(f.ret.clone())));
+                }
+                Item::Enum(e) => {
+                    self.enums.insert(e.name.clone(), e.clone());
+                }
+            }
+        }
+
+        for item in &program.items {
+            match item {
+                Item::Const(c) => {
+                    let actual = self.type_of_expr(&c.value)?;
+                    self.expect_assignable(&actual, &c.ty, &format!("const `{}`", c.name))?;
+                }
+                Item::Function(f) => self.check_function(f)?,
+                Item::Enum(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn check_function(&mut self, f: &Function) -> TypeResult<()> {
+        self.current_return = f.ret.clone();
+        self.scopes.clear();
+        self.scopes.push(HashMap::new());
+        for p in &f.params {
+            self.scopes.last_mut().unwrap().insert(p.name.clone(), p.ty.clone());
+        }
+        self.check_block(&f.body)?;
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn check_block(&mut self, stmts: &[Stmt]) -> TypeResult<()> {
+        self.scopes.push(HashMap::new());
+        for stmt in stmts {
+            self.check_stmt(stmt)?;
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) -> TypeResult<()> {
+        match stmt {
+            Stmt::Let { name, ty, init } => {
+                let actual = self.type_of_expr(init)?;
+                self.expect_assignable(&actual, ty, &format!("let `{}`", name))?;
+                self.scopes.last_mut().unwrap().insert(name.clone(), ty.clone());
+            }
+            Stmt::Assign { target, value } => {
+                if let Expr::Ident(name) = target {
+                    if self.is_const_or_fn_name(name) {
+                        return Err(TypeError(format!("cannot assign to `{}`: consts and functions are not mutable storage", name)));
+                    }
+                }
+                let target_ty = self.type_of_expr(target)?;
+                let value_ty = self.type_of_expr(value)?;
+                self.expect_assignable(&value_ty, &target_ty, "assignment")?;
+            }
+            Stmt::Expr(e) => {
+                self.type_of_expr(e)?;
+            }
+            Stmt::Return(value) => {
+                let actual = match value {
+                    Some(e) => self.type_of_expr(e)?,
+                    None => Type::Void,
+                };
+                self.expect_assignable(&actual, &self.current_return.clone(), "return")?;
+            }
+            Stmt::If { cond, then_branch, else_branch } => {
+                let cond_ty = self.type_of_expr(cond)?;
+                if !self.is_condition_type(&cond_ty) {
+                    return Err(TypeError(format!("if condition must be bool, found {}", cond_ty)));
+                }
+                self.check_block(then_branch)?;
+                if let Some(else_b) = else_branch {
+                    self.check_block(else_b)?;
+                }
+            }
+            Stmt::While { cond, body } => {
+                let cond_ty = self.type_of_expr(cond)?;
+                if !self.is_condition_type(&cond_ty) {
+                    return Err(TypeError(format!("while condition must be bool, found {}", cond_ty)));
+                }
+                self.check_block(body)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn enum_info(&self, name: &str) -> TypeResult<&EnumDecl> {
+        self.enums.get(name).ok_or_else(|| TypeError(format!("unknown enum `{}`", name)))
+    }
+
+    /// True for `bool` and for a two-variant enum: the boolean-
+    /// representation optimization makes a two-variant enum directly
+    /// usable as an `if`/`while` condition, the same way `test_logic`'s
+    /// bool conditions already are, without an explicit `!= 0`.
+    fn is_condition_type(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Bool => true,
+            Type::Enum(name) => self.enums.get(name).is_some_and(|e| e.variants.len() == 2),
+            _ => false,
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Type> {
+        for scope in self.scopes.iter().rev() {
+            if let Some(ty) = scope.get(name) {
+                return Some(ty.clone());
+            }
+        }
+        self.globals.get(name).cloned()
+    }
+
+    /// A name resolves through `self.globals` only when no enclosing
+    /// scope shadows it with a `let`/parameter binding - exactly the
+    /// case where it names a top-level `const` or `fn` rather than
+    /// mutable local storage.
+    fn is_const_or_fn_name(&self, name: &str) -> bool {
+        !self.scopes.iter().any(|scope| scope.contains_key(name)) && self.globals.contains_key(name)
+    }
+
+    fn type_of_expr(&mut self, expr: &Expr) -> TypeResult<Type> {
+        match expr {
+            Expr::IntLit(_) => Ok(Type::I32),
+            // Narrowest default, same reasoning as `IntLit` -> `I32`: a
+            // bare literal like `1.0` should widen into either `f32` or
+            // `f64` context without an explicit cast, which only works
+            // if its own static type is the narrower of the two.
+            Expr::FloatLit(_) => Ok(Type::F32),
+            Expr::BoolLit(_) => Ok(Type::Bool),
+            Expr::Ident(name) => self
+                .lookup(name)
+                .ok_or_else(|| TypeError(format!("use of undeclared identifier `{}`", name))),
+            Expr::Unary(op, inner) => self.type_of_unary(*op, inner),
+            Expr::Binary(op, lhs, rhs) => self.type_of_binary(*op, lhs, rhs),
+            Expr::Call(callee, args) => self.type_of_call(callee, args),
+            Expr::Index(base, index) => {
+                let base_ty = self.type_of_expr(base)?;
+                let index_ty = self.type_of_expr(index)?;
+                if !index_ty.is_integer() {
+                    return Err(TypeError(format!("array index must be an integer, found {}", index_ty)));
+                }
+                match base_ty {
+                    Type::Array(elem, _) => Ok(*elem),
+                    other => Err(TypeError(format!("cannot index non-array type {}", other))),
+                }
+            }
+            Expr::Cast(inner, target) => {
+                let from = self.type_of_expr(inner)?;
+                // An enum only ever casts to its own declared underlying
+                // type - `Type::castable_to` doesn't know which type that
+                // is (it isn't numeric, so its generic numeric path would
+                // just reject everything), so this is resolved against
+                // the enum registry instead.
+                if let Type::Enum(name) = &from {
+                    let info = self.enum_info(name)?;
+                    return if *target == info.underlying {
+                        Ok(target.clone())
+                    } else {
+                        Err(TypeError(format!("cannot cast {} as {}; only {} is allowed", from, target, info.underlying)))
+                    };
+                }
+                if !from.castable_to(target) {
+                    return Err(TypeError(format!("cannot cast {} as {}", from, target)));
+                }
+                Ok(target.clone())
+            }
+            Expr::EnumVariant(enum_name, variant) => {
+                let info = self.enum_info(enum_name)?;
+                if !info.variants.iter().any(|v| v == variant) {
+                    return Err(TypeError(format!("enum `{}` has no variant `{}`", enum_name, variant)));
+                }
+                Ok(Type::Enum(enum_name.clone()))
+            }
+            Expr::ArrayLit(elems) => {
+                if elems.is_empty() {
+                    return Err(TypeError("cannot infer type of empty array literal".into()));
+                }
+                let mut elem_ty = self.type_of_expr(&elems[0])?;
+                for e in &elems[1..] {
+                    let ty = self.type_of_expr(e)?;
+                    elem_ty = Type::promote(&elem_ty, &ty)
+                        .ok_or_else(|| TypeError(format!("array elements have incompatible types {} and {}", elem_ty, ty)))?;
+                }
+                Ok(Type::Array(Box::new(elem_ty), Some(elems.len())))
+            }
+        }
+    }
+
+    fn type_of_unary(&mut self, op: UnaryOp, inner: &Expr) -> TypeResult<Type> {
+        let inner_ty = self.type_of_expr(inner)?;
+        match op {
+            UnaryOp::Neg => {
+                if !inner_ty.is_numeric() {
+                    return Err(TypeError(format!("unary `-` requires a numeric operand, found {}", inner_ty)));
+                }
+                Ok(inner_ty)
+            }
+            UnaryOp::BitNot => {
+                if !inner_ty.is_integer() {
+                    return Err(TypeError(format!("unary `~` requires an integer operand, found {}", inner_ty)));
+                }
+                Ok(inner_ty)
+            }
+            UnaryOp::Ref => Ok(Type::Pointer(Box::new(inner_ty))),
+            UnaryOp::Deref => match inner_ty {
+                Type::Pointer(pointee) => Ok(*pointee),
+                other => Err(TypeError(format!("cannot dereference non-pointer type {}", other))),
+            },
+        }
+    }
+
+    fn type_of_binary(&mut self, op: BinOp, lhs: &Expr, rhs: &Expr) -> TypeResult<Type> {
+        let lhs_ty = self.type_of_expr(lhs)?;
+        let rhs_ty = self.type_of_expr(rhs)?;
+
+        if op.is_bitwise() {
+            if !lhs_ty.is_integer() || !rhs_ty.is_integer() {
+                return Err(TypeError(format!(
+                    "bitwise/shift operator requires integer operands, found {} and {}",
+                    lhs_ty, rhs_ty
+                )));
+            }
+            return match op {
+                // The shift amount doesn't participate in promotion: `x
+                // << n` keeps `x`'s type regardless of `n`'s width.
+                BinOp::Shl | BinOp::Shr => Ok(lhs_ty),
+                _ => Type::promote(&lhs_ty, &rhs_ty)
+                    .ok_or_else(|| TypeError(format!("cannot unify {} and {}", lhs_ty, rhs_ty))),
+            };
+        }
+
+        if op.is_comparison() {
+            // An enum has no implicit arithmetic or ordering - only
+            // `==`/`!=` make it past `Type::promote`'s same-type
+            // shortcut, the same restriction arithmetic operators
+            // enforce below for `+`/`-`/etc.
+            if matches!(op, BinOp::Lt | BinOp::Gt | BinOp::Le | BinOp::Ge) && (matches!(lhs_ty, Type::Enum(_)) || matches!(rhs_ty, Type::Enum(_))) {
+                return Err(TypeError(format!("enum {} has no ordering; only == and != are supported", lhs_ty)));
+            }
+            Type::promote(&lhs_ty, &rhs_ty)
+                .ok_or_else(|| TypeError(format!("cannot compare {} and {}", lhs_ty, rhs_ty)))?;
+            return Ok(Type::Bool);
+        }
+
+        // Arithmetic requires actual numbers: an enum is distinct from
+        // its underlying integer (only an explicit `as` cast reaches
+        // it), so it must be rejected here even though `Type::promote`'s
+        // same-type shortcut would otherwise happily "promote" two
+        // values of the same enum to themselves.
+        if !lhs_ty.is_numeric() || !rhs_ty.is_numeric() {
+            return Err(TypeError(format!("arithmetic operator requires numeric operands, found {} and {}", lhs_ty, rhs_ty)));
+        }
+        Type::promote(&lhs_ty, &rhs_ty)
+            .ok_or_else(|| TypeError(format!("cannot apply operator to {} and {}", lhs_ty, rhs_ty)))
+    }
+
+    fn type_of_call(&mut self, callee: &Expr, args: &[Expr]) -> TypeResult<Type> {
+        if let Expr::Ident(name) = callee {
+            if let Some(intrinsic) = Intrinsic::from_name(name) {
+                return self.type_of_intrinsic_call(intrinsic, name, args);
+            }
+        }
+
+        let callee_ty = self.type_of_expr(callee)?;
+        let (params, ret) = match callee_ty {
+            Type::Function(params, ret) => (params, ret),
+            other => return Err(TypeError(format!("cannot call non-function type {}", other))),
+        };
+        if params.len() != args.len() {
+            return Err(TypeError(format!("expected {} arguments, found {}", params.len(), args.len())));
+        }
+        for (param_ty, arg) in params.iter().zip(args) {
+            let arg_ty = self.type_of_expr(arg)?;
+            self.expect_assignable(&arg_ty, param_ty, "call argument")?;
+        }
+        Ok(*ret)
+    }
+
+    fn type_of_intrinsic_call(&mut self, intrinsic: Intrinsic, name: &str, args: &[Expr]) -> TypeResult<Type> {
+        if args.len() != intrinsic.arity() {
+            return Err(TypeError(format!(
+                "`{}` expects {} argument(s), found {}",
+                name,
+                intrinsic.arity(),
+                args.len()
+            )));
+        }
+        let arg_types: Vec<Type> = args.iter().map(|a| self.type_of_expr(a)).collect::<TypeResult<_>>()?;
+
+        if intrinsic == Intrinsic::Scalbn {
+            let float_ty = intrinsics::float_operand_type(&arg_types[..1])
+                .ok_or_else(|| TypeError(format!("`{}`'s first argument must be numeric", name)))?;
+            if !arg_types[1].is_integer() {
+                return Err(TypeError(format!("`{}`'s second argument must be an integer, found {}", name, arg_types[1])));
+            }
+            return Ok(float_ty);
+        }
+
+        intrinsics::float_operand_type(&arg_types)
+            .ok_or_else(|| TypeError(format!("`{}` requires numeric arguments", name)))
+    }
+
+    /// True if a value of type `from` may flow into a slot of type `to`,
+    /// either because they match exactly, because `from` implicitly
+    /// promotes up to `to` (never down - narrowing needs an explicit
+    /// cast), or because `to` is an inferred-size array (`i32[]`) and
+    /// `from` is a concretely-sized array of an assignable element
+    /// type - the `[]` in a declaration just defers the length to
+    /// whatever the initializer turns out to have.
+    fn assignable(&self, from: &Type, to: &Type) -> bool {
+        if from == to {
+            return true;
+        }
+        if let (Type::Array(from_elem, _), Type::Array(to_elem, None)) = (from, to) {
+            return self.assignable(from_elem, to_elem);
+        }
+        match Type::promote(from, to) {
+            Some(common) => common == *to,
+            None => false,
+        }
+    }
+
+    fn expect_assignable(&self, from: &Type, to: &Type, context: &str) -> TypeResult<()> {
+        if self.assignable(from, to) {
+            Ok(())
+        } else {
+            Err(TypeError(format!("{}: expected {}, found {}", context, to, from)))
+        }
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
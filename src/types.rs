@@ -0,0 +1,108 @@
+//! Static types and the numeric-promotion lattice shared by the
+//! type-checker and the evaluator.
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    I32,
+    I64,
+    U32,
+    U64,
+    F32,
+    F64,
+    Bool,
+    Void,
+    Pointer(Box<Type>),
+    Array(Box<Type>, Option<usize>),
+    Function(Vec<Type>, Box<Type>),
+    /// A user-defined enum, identified nominally by name - distinct from
+    /// its underlying integer type (see `EnumDecl`) even when two enums
+    /// happen to share an underlying type.
+    Enum(String),
+}
+
+impl Type {
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Type::I32 | Type::I64 | Type::U32 | Type::U64)
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::F32 | Type::F64)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.is_integer() || self.is_float()
+    }
+
+    /// The common type two numeric operands promote to, per the ranking
+    /// `test_promotions` exercises: float beats int, f64 beats f32,
+    /// wider integer beats narrower, same-width mixed signedness goes
+    /// unsigned.
+    pub fn promote(a: &Type, b: &Type) -> Option<Type> {
+        if a == b {
+            return Some(a.clone());
+        }
+        if !a.is_numeric() || !b.is_numeric() {
+            return None;
+        }
+        if a.is_float() || b.is_float() {
+            return Some(if *a == Type::F64 || *b == Type::F64 {
+                Type::F64
+            } else {
+                Type::F32
+            });
+        }
+        let rank = |t: &Type| match t {
+            Type::I32 => 0,
+            Type::U32 => 1,
+            Type::I64 => 2,
+            Type::U64 => 3,
+            _ => unreachable!(),
+        };
+        Some(if rank(a) >= rank(b) { a.clone() } else { b.clone() })
+    }
+
+    /// Whether an explicit `expr as to` cast is permitted from this type.
+    /// Unlike `promote`, this allows narrowing (i64 -> i32), float<->int,
+    /// and pointer<->integer reinterpretation - anything implicit
+    /// promotion would reject.
+    pub fn castable_to(&self, to: &Type) -> bool {
+        if self.is_numeric() && to.is_numeric() {
+            return true;
+        }
+        match (self, to) {
+            (Type::Pointer(_), Type::Pointer(_)) => true,
+            (Type::Pointer(_), t) if t.is_integer() => true,
+            (t, Type::Pointer(_)) if t.is_integer() => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
+            Type::U32 => write!(f, "u32"),
+            Type::U64 => write!(f, "u64"),
+            Type::F32 => write!(f, "f32"),
+            Type::F64 => write!(f, "f64"),
+            Type::Bool => write!(f, "bool"),
+            Type::Void => write!(f, "void"),
+            Type::Pointer(t) => write!(f, "{}*", t),
+            Type::Array(t, Some(n)) => write!(f, "{}[{}]", t, n),
+            Type::Array(t, None) => write!(f, "{}[]", t),
+            Type::Function(params, ret) => {
+                write!(f, "fn(")?;
+                for (i, p) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", p)?;
+                }
+                write!(f, ") -> {}", ret)
+            }
+            Type::Enum(name) => write!(f, "{}", name),
+        }
+    }
+}